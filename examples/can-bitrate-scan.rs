@@ -0,0 +1,112 @@
+#![no_main]
+#![no_std]
+
+use crate::hal::{
+    can::CanExt,
+    fdcan::{
+        config::{NominalBitTiming, DEFAULT_SAMPLE_POINT},
+        filter::{ExtendedFilter, ExtendedFilterSlot, StandardFilter, StandardFilterSlot},
+        interrupt::Interrupt,
+    },
+    gpio::{GpioExt as _, Speed},
+    rcc::{Config, RccExt, SysClockSrc},
+    stm32::Peripherals,
+    time::{Hertz, U32Ext},
+};
+use stm32g4xx_hal as hal;
+
+use cortex_m_rt::entry;
+
+use log::info;
+
+#[macro_use]
+mod utils;
+
+/// Bit rates to try, from fast to slow
+const CANDIDATES: [u32; 6] = [1_000_000, 500_000, 250_000, 125_000, 100_000, 50_000];
+
+/// Number of times the bus is polled for each candidate, about 100ms
+const POLLS: u32 = 1_000;
+
+/// FDCAN kernel clock: the P clock, which runs from the 24MHz HSE
+const CLOCK: Hertz = Hertz(24_000_000);
+
+#[entry]
+fn main() -> ! {
+    utils::logger::init();
+
+    info!("Start");
+
+    info!("Init Clocks");
+
+    let dp = Peripherals::take().unwrap();
+    let _cp = cortex_m::Peripherals::take().expect("cannot take core peripherals");
+    let rcc = dp.RCC.constrain();
+    let mut rcc = rcc.freeze(Config::new(SysClockSrc::HSE(24.mhz())));
+
+    info!("Split GPIO");
+
+    let gpiob = dp.GPIOB.split(&mut rcc);
+
+    let mut can = {
+        info!("Init CAN 1");
+        let rx = gpiob.pb8.into_alternate().set_speed(Speed::VeryHigh);
+        let tx = gpiob.pb9.into_alternate().set_speed(Speed::VeryHigh);
+
+        info!("-- Create CAN 1 instance");
+        let mut can = dp.FDCAN1.fdcan(tx, rx, &rcc);
+        can.set_protocol_exception_handling(false);
+
+        info!("-- Configure Filters");
+        // Store every frame on the bus into FIFO 0
+        can.set_standard_filter(
+            StandardFilterSlot::_0,
+            StandardFilter::accept_all_into_fifo0(),
+        );
+        can.set_extended_filter(
+            ExtendedFilterSlot::_0,
+            ExtendedFilter::accept_all_into_fifo0(),
+        );
+        can
+    };
+
+    let mut found = None;
+
+    for &bitrate in CANDIDATES.iter() {
+        info!("Trying {} bit/s", bitrate);
+        // All candidates divide the kernel clock exactly
+        let btr =
+            NominalBitTiming::from_bitrate(CLOCK, Hertz(bitrate), DEFAULT_SAMPLE_POINT).unwrap();
+        can.set_nominal_bit_timing(btr);
+
+        // The peripheral only listens, so a wrong bit rate does not disturb the bus
+        let mut monitor = can.into_bus_monitoring();
+        monitor.clear_interrupt(Interrupt::ProtErrArbritation);
+
+        let mut frames = 0_u32;
+        for _ in 0..POLLS {
+            while monitor.receive0(&mut |_, _| ()).is_ok() {
+                frames += 1;
+            }
+            cortex_m::asm::delay(2_400);
+        }
+        // A wrong bit rate shows up as protocol errors, such as stuff or form errors
+        let errors = monitor.has_interrupt(Interrupt::ProtErrArbritation);
+        info!("-- {} frames received, errors: {}", frames, errors);
+
+        can = monitor.into_config_mode();
+        if frames > 0 && !errors {
+            found = Some(bitrate);
+            break;
+        }
+    }
+
+    match found {
+        Some(bitrate) => info!("Bus runs at {} bit/s", bitrate),
+        None => info!("No traffic received without errors, is the bus idle?"),
+    }
+
+    loop {
+        cortex_m::asm::nop();
+    }
+}
@@ -3,18 +3,17 @@
 
 use crate::hal::{
     can::CanExt,
+    fdcan::{
+        config::NominalBitTiming,
+        filter::{StandardFilter, StandardFilterSlot},
+        frame::TxFrameHeader,
+        id::StandardId,
+    },
     gpio::{GpioExt as _, Speed},
-    nb::block,
     rcc::{Config, RccExt, SysClockSrc},
     stm32::Peripherals,
     time::U32Ext,
 };
-use fdcan::{
-    config::NominalBitTiming,
-    filter::{StandardFilter, StandardFilterSlot},
-    frame::{FrameFormat, TxFrameHeader},
-    id::StandardId,
-};
 use stm32g4xx_hal as hal;
 
 use core::num::{NonZeroU16, NonZeroU8};
@@ -37,7 +36,7 @@ fn main() -> ! {
     // TODO: use the can_bit_timings crate
     let btr = NominalBitTiming {
         prescaler: NonZeroU16::new(12).unwrap(),
-        seg1: NonZeroU8::new(13).unwrap(),
+        seg1: NonZeroU16::new(13).unwrap(),
         seg2: NonZeroU8::new(2).unwrap(),
         sync_jump_width: NonZeroU8::new(1).unwrap(),
     };
@@ -66,9 +65,10 @@ fn main() -> ! {
         can.set_nominal_bit_timing(btr);
 
         info!("-- Configure Filters");
+        // Route all standard frames into FIFO 1, to keep FIFO 0 free for other traffic
         can.set_standard_filter(
             StandardFilterSlot::_0,
-            StandardFilter::accept_all_into_fifo0(),
+            StandardFilter::accept_all_into_fifo1(),
         );
 
         info!("-- Current Config: {:#?}", can.get_config());
@@ -104,23 +104,17 @@ fn main() -> ! {
     let mut can = can1;
 
     info!("Create Message Data");
-    let mut buffer: [u8; 8] = [0xAA, 0xAA, 0xAA, 0xAA, 0xFF, 0xFF, 0xFF, 0xFF];
+    let buffer: [u8; 8] = [0xAA, 0xAA, 0xAA, 0xAA, 0xFF, 0xFF, 0xFF, 0xFF];
     info!("Create Message Header");
-    let header = TxFrameHeader {
-        len: 2 * 4,
-        id: StandardId::new(0x1).unwrap().into(),
-        frame_format: FrameFormat::Standard,
-        bit_rate_switching: false,
-        marker: None,
-    };
+    let header = TxFrameHeader::new(StandardId::new(0x1).unwrap().into(), 2 * 4);
     info!("Initial Header: {:#X?}", &header);
 
     info!("Transmit initial message");
-    block!(can.transmit(header, &buffer)).unwrap();
+    can.write_blocking(header, &buffer).unwrap();
 
     loop {
-        if let Ok(rxheader) = block!(can.receive0(&mut buffer)) {
-            block!(can.transmit(rxheader.unwrap().to_tx_header(None), &mut buffer)).unwrap();
-        }
+        let frame = can.read_blocking();
+        can.write_blocking(frame.header.to_tx_header(None), frame.data())
+            .unwrap();
     }
 }
@@ -0,0 +1,133 @@
+#![no_main]
+#![no_std]
+
+use crate::hal::{
+    can::CanExt,
+    fdcan::{
+        config::{DataBitTiming, FrameTransmissionConfig, NominalBitTiming},
+        filter::{StandardFilter, StandardFilterSlot},
+        frame::TxFrameHeader,
+        id::StandardId,
+    },
+    gpio::{GpioExt as _, Speed},
+    rcc::{Config, RccExt, SysClockSrc},
+    stm32::Peripherals,
+    time::U32Ext,
+};
+use stm32g4xx_hal as hal;
+
+use core::num::{NonZeroU16, NonZeroU8};
+
+use cortex_m_rt::entry;
+
+use log::info;
+
+#[macro_use]
+mod utils;
+
+#[entry]
+fn main() -> ! {
+    utils::logger::init();
+
+    info!("Start");
+
+    // APB1 (HSE): 24MHz, Bit rate: 125kBit/s, Sample Point 87.5%
+    // Value was calculated with http://www.bittiming.can-wiki.info/
+    let btr = NominalBitTiming {
+        prescaler: NonZeroU16::new(12).unwrap(),
+        seg1: NonZeroU16::new(13).unwrap(),
+        seg2: NonZeroU8::new(2).unwrap(),
+        sync_jump_width: NonZeroU8::new(1).unwrap(),
+    };
+
+    // APB1 (HSE): 24MHz, Data Bit rate: 2MBit/s, Sample Point 75%
+    let dbtr = DataBitTiming {
+        transceiver_delay_compensation: false,
+        prescaler: NonZeroU8::new(1).unwrap(),
+        seg1: NonZeroU8::new(8).unwrap(),
+        seg2: NonZeroU8::new(3).unwrap(),
+        sync_jump_width: NonZeroU8::new(2).unwrap(),
+    };
+
+    info!("Init Clocks");
+
+    let dp = Peripherals::take().unwrap();
+    let _cp = cortex_m::Peripherals::take().expect("cannot take core peripherals");
+    let rcc = dp.RCC.constrain();
+    let mut rcc = rcc.freeze(Config::new(SysClockSrc::HSE(24.mhz())));
+
+    info!("Split GPIO");
+
+    let gpiob = dp.GPIOB.split(&mut rcc);
+
+    let mut can = {
+        info!("Init CAN 1");
+        let rx = gpiob.pb8.into_alternate().set_speed(Speed::VeryHigh);
+        let tx = gpiob.pb9.into_alternate().set_speed(Speed::VeryHigh);
+
+        info!("-- Create CAN 1 instance");
+        let mut can = dp.FDCAN1.fdcan(tx, rx, &rcc);
+        can.set_protocol_exception_handling(false);
+
+        info!("-- Configure nominal and data timing");
+        can.set_nominal_bit_timing(btr);
+        can.set_data_bit_timing(dbtr);
+        can.set_frame_transmit(FrameTransmissionConfig::AllowFdCanAndBRS);
+
+        info!("-- Configure Filters");
+        can.set_standard_filter(
+            StandardFilterSlot::_0,
+            StandardFilter::accept_all_into_fifo0(),
+        );
+
+        info!("-- Current Config: {:#?}", can.get_config());
+
+        info!("-- Set CAN1 in to internal loopback mode");
+        // Transmitted frames are received back, no transceiver or bus is needed
+        can.into_internal_loopback()
+    };
+
+    info!("Create Message Data");
+    let mut buffer = [0_u8; 64];
+    for (i, b) in buffer.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+
+    info!("Echo every FD payload size");
+    // Sizes above 8 bytes are sent with a Data Length Code of 9 to 15, sizes which are not a
+    // multiple of 4 end in a partially used data word
+    for &len in [0_u8, 1, 2, 3, 4, 5, 6, 7, 8, 12, 16, 20, 24, 32, 48, 64].iter() {
+        let header = TxFrameHeader::new(StandardId::new(0x2).unwrap().into(), len)
+            .fd()
+            .brs();
+        can.write_blocking(header, &buffer[..len as usize]).unwrap();
+
+        let frame = can.read_blocking();
+        let ok = frame.header.len() == len
+            && frame.header.is_fd_frame()
+            && frame.header.bit_rate_switching()
+            && frame.data() == &buffer[..len as usize];
+        info!("-- {} bytes: {}", len, if ok { "ok" } else { "MISMATCH" });
+    }
+
+    info!("Create Message Header");
+    let header = TxFrameHeader::new(StandardId::new(0x1).unwrap().into(), buffer.len() as u8)
+        .fd()
+        .brs();
+    info!("Initial Header: {:#X?}", &header);
+
+    info!("Transmit initial message");
+    can.write_blocking(header, &buffer).unwrap();
+
+    loop {
+        let frame = can.read_blocking();
+        info!(
+            "Received {} bytes, fd: {}, brs: {}",
+            frame.header.len(),
+            frame.header.is_fd_frame(),
+            frame.header.bit_rate_switching()
+        );
+        can.write_blocking(frame.header.to_tx_header(None), frame.data())
+            .unwrap();
+    }
+}
@@ -0,0 +1,134 @@
+#![no_main]
+#![no_std]
+
+use crate::hal::{
+    can::{Can, CanExt},
+    fdcan::{
+        config::NominalBitTiming,
+        filter::{StandardFilter, StandardFilterSlot},
+        frame::TxFrameHeader,
+        id::StandardId,
+        interrupt::{Interrupt, InterruptLine, Interrupts},
+        interrupt_line_vector, FdCanControl, Fifo0, NormalOperationMode, Rx,
+    },
+    gpio::{
+        gpiob::{PB8, PB9},
+        Alternate, GpioExt as _, Speed, AF9,
+    },
+    nb::block,
+    rcc::{Config, RccExt, SysClockSrc},
+    stm32::{interrupt, Peripherals, FDCAN1},
+    time::U32Ext,
+};
+use stm32g4xx_hal as hal;
+
+use core::cell::RefCell;
+use core::num::{NonZeroU16, NonZeroU8};
+
+use cortex_m::interrupt::Mutex;
+use cortex_m_rt::entry;
+
+use log::info;
+
+#[macro_use]
+mod utils;
+
+type Can1 = Can<FDCAN1, (PB9<Alternate<AF9>>, PB8<Alternate<AF9>>)>;
+type Can1Rx = (
+    FdCanControl<Can1, NormalOperationMode>,
+    Rx<Can1, NormalOperationMode, Fifo0>,
+);
+
+// The receiving half is shared with the interrupt handler
+static G_CAN_RX: Mutex<RefCell<Option<Can1Rx>>> = Mutex::new(RefCell::new(None));
+
+// Triggered by interrupt line 0 of FDCAN1, which all interrupts are routed to
+#[interrupt]
+fn FDCAN1_INTR0_IT() {
+    cortex_m::interrupt::free(|cs| {
+        if let Some((control, rx)) = G_CAN_RX.borrow(cs).borrow_mut().as_mut() {
+            // Clear the flag before draining, so a frame arriving meanwhile triggers again
+            control.clear_interrupts(Interrupts::RX_FIFO_0_NEW_MESSAGE);
+
+            while let Ok(frame) = rx.receive_frame() {
+                let frame = frame.unwrap();
+                info!("Received {:?}: {:?}", frame.header.id, frame.data());
+            }
+        }
+    });
+}
+
+#[entry]
+fn main() -> ! {
+    utils::logger::init();
+
+    info!("Start");
+
+    // APB1 (HSE): 24MHz, Bit rate: 125kBit/s, Sample Point 87.5%
+    // Value was calculated with http://www.bittiming.can-wiki.info/
+    let btr = NominalBitTiming {
+        prescaler: NonZeroU16::new(12).unwrap(),
+        seg1: NonZeroU16::new(13).unwrap(),
+        seg2: NonZeroU8::new(2).unwrap(),
+        sync_jump_width: NonZeroU8::new(1).unwrap(),
+    };
+
+    info!("Init Clocks");
+
+    let dp = Peripherals::take().unwrap();
+    let _cp = cortex_m::Peripherals::take().expect("cannot take core peripherals");
+    let rcc = dp.RCC.constrain();
+    let mut rcc = rcc.freeze(Config::new(SysClockSrc::HSE(24.mhz())));
+
+    info!("Split GPIO");
+
+    let gpiob = dp.GPIOB.split(&mut rcc);
+
+    let can = {
+        info!("Init CAN 1");
+        let rx = gpiob.pb8.into_alternate().set_speed(Speed::VeryHigh);
+        let tx = gpiob.pb9.into_alternate().set_speed(Speed::VeryHigh);
+
+        info!("-- Create CAN 1 instance");
+        let mut can = dp.FDCAN1.fdcan(tx, rx, &rcc);
+        can.set_protocol_exception_handling(false);
+
+        info!("-- Configure nominal timing");
+        can.set_nominal_bit_timing(btr);
+
+        info!("-- Configure Filters");
+        can.set_standard_filter(
+            StandardFilterSlot::_0,
+            StandardFilter::accept_all_into_fifo0(),
+        );
+
+        info!("-- Configure Interrupts");
+        // Route all interrupts to line 0, then enable the Rx FIFO 0 new message interrupt
+        can.set_interrupt_line_config(Interrupts::none());
+        can.enable_interrupt(Interrupt::RxFifo0NewMsg);
+        can.enable_interrupt_line(InterruptLine::_0, true);
+
+        info!("-- Set CAN1 in to normal mode");
+        can.into_normal()
+    };
+
+    let (control, mut tx, rx0, _rx1) = can.split();
+    cortex_m::interrupt::free(|cs| G_CAN_RX.borrow(cs).replace(Some((control, rx0))));
+
+    info!("Enable FDCAN1 Interrupt");
+    unsafe {
+        cortex_m::peripheral::NVIC::unmask(interrupt_line_vector::<Can1>(InterruptLine::_0));
+    }
+
+    let header = TxFrameHeader::new(StandardId::new(0x1).unwrap().into(), 2);
+
+    let mut counter = 0_u8;
+    loop {
+        info!("Transmit message {}", counter);
+        let word = u32::from_le_bytes([0xAA, counter, 0, 0]);
+        block!(tx.transmit(header, &mut |words| words[0] = word)).unwrap();
+        counter = counter.wrapping_add(1);
+
+        cortex_m::asm::delay(24_000_000);
+    }
+}
@@ -0,0 +1,97 @@
+#![no_main]
+#![no_std]
+
+use crate::hal::{
+    can::CanExt,
+    fdcan::{
+        config::NominalBitTiming,
+        filter::{ExtendedFilter, ExtendedFilterSlot, StandardFilter, StandardFilterSlot},
+    },
+    gpio::{GpioExt as _, Speed},
+    rcc::{Config, RccExt, SysClockSrc},
+    stm32::Peripherals,
+    time::U32Ext,
+};
+use stm32g4xx_hal as hal;
+
+use core::num::{NonZeroU16, NonZeroU8};
+
+use cortex_m_rt::entry;
+
+use log::info;
+
+#[macro_use]
+mod utils;
+
+#[entry]
+fn main() -> ! {
+    utils::logger::init();
+
+    info!("Start");
+
+    // APB1 (HSE): 24MHz, Bit rate: 125kBit/s, Sample Point 87.5%
+    // Value was calculated with http://www.bittiming.can-wiki.info/
+    let btr = NominalBitTiming {
+        prescaler: NonZeroU16::new(12).unwrap(),
+        seg1: NonZeroU16::new(13).unwrap(),
+        seg2: NonZeroU8::new(2).unwrap(),
+        sync_jump_width: NonZeroU8::new(1).unwrap(),
+    };
+
+    info!("Init Clocks");
+
+    let dp = Peripherals::take().unwrap();
+    let _cp = cortex_m::Peripherals::take().expect("cannot take core peripherals");
+    let rcc = dp.RCC.constrain();
+    let mut rcc = rcc.freeze(Config::new(SysClockSrc::HSE(24.mhz())));
+
+    info!("Split GPIO");
+
+    let gpiob = dp.GPIOB.split(&mut rcc);
+
+    let can = {
+        info!("Init CAN 1");
+        let rx = gpiob.pb8.into_alternate().set_speed(Speed::VeryHigh);
+        let tx = gpiob.pb9.into_alternate().set_speed(Speed::VeryHigh);
+
+        info!("-- Create CAN 1 instance");
+        let mut can = dp.FDCAN1.fdcan(tx, rx, &rcc);
+        can.set_protocol_exception_handling(false);
+
+        info!("-- Configure nominal timing");
+        can.set_nominal_bit_timing(btr);
+
+        info!("-- Configure Filters");
+        // Store every frame on the bus into FIFO 0
+        can.set_standard_filter(
+            StandardFilterSlot::_0,
+            StandardFilter::accept_all_into_fifo0(),
+        );
+        can.set_extended_filter(
+            ExtendedFilterSlot::_0,
+            ExtendedFilter::accept_all_into_fifo0(),
+        );
+
+        info!("-- Set CAN1 in to bus monitoring mode");
+        // The peripheral only listens, it does not acknowledge frames or send error frames
+        can.into_bus_monitoring()
+    };
+
+    let (control, mut rx0, _rx1) = can.split_rx();
+    let mut dropped = 0;
+
+    loop {
+        rx0.drain(|header, words| {
+            info!(
+                "[{:>5}] {:?} len: {} rtr: {} data: {:08X?}",
+                header.time_stamp, header.id, header.len, header.rtr, words,
+            );
+        });
+
+        let count = control.dropped_frame_count();
+        if count != dropped {
+            info!("{} frames lost", count.wrapping_sub(dropped));
+            dropped = count;
+        }
+    }
+}
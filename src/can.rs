@@ -1,8 +1,8 @@
 //! # Controller Area Network (CAN) Interface
 //!
 
+use crate::fdcan::{self, ConfigMode, FdCan, PoweredDownMode};
 use crate::rcc::{self, Rcc};
-use fdcan;
 
 mod sealed {
     /// A TX pin configured for CAN communication
@@ -11,29 +11,47 @@ mod sealed {
     pub trait Rx<CAN> {}
 }
 
-/// Select an FDCAN Clock Source
-#[allow(dead_code)]
-enum FdCanClockSource {
-    /// Select HSE as the FDCAN clock source
-    HSE = 0b00,
-    /// Select PLL "Q" clock as the FDCAN clock source
-    PLLQ = 0b01,
-    /// Select "P" clock as the FDCAN clock source
-    PCLK = 0b10,
-    //Reserved = 0b10,
-}
-
 /// Storage type for the CAN controller
+///
+/// `PINS` holds the TX and RX pins handed to [`CanExt::fdcan`], so they can not be reused while
+/// the peripheral is active.
 #[derive(Debug)]
-pub struct Can<FDCAN> {
+pub struct Can<FDCAN, PINS = ()> {
     rb: FDCAN,
+    pins: PINS,
 }
 #[allow(dead_code)]
-impl<FDCAN> Can<FDCAN> {
+impl<FDCAN, PINS> Can<FDCAN, PINS> {
     /// Returns a reference to the inner peripheral
     fn inner(&self) -> &FDCAN {
         &self.rb
     }
+
+    /// Releases the peripheral and the pins it was created with
+    ///
+    /// Use `FdCan::free` to get the `Can` back from an FdCan instance.
+    pub fn release(self) -> (FDCAN, PINS) {
+        (self.rb, self.pins)
+    }
+}
+impl<FDCAN, PINS> Can<FDCAN, PINS>
+where
+    FDCAN: rcc::Instance,
+{
+    /// Disables the peripheral clock, then releases the peripheral and its pins
+    ///
+    /// Use [`Can::release`] to keep the clock running.
+    ///
+    /// # Safety
+    ///
+    /// The FDCAN clock enable bit is shared by all instances, so this stops the other FDCAN
+    /// instances as well. The caller must ensure that no other FDCAN instance is in use, or will
+    /// be used without enabling the clock again. This also applies to instances created with
+    /// [`CanExt::fdcan_unchecked`], which never enabled the clock themselves.
+    pub unsafe fn free(self, rcc: &Rcc) -> (FDCAN, PINS) {
+        FDCAN::disable(&rcc.rb);
+        self.release()
+    }
 }
 
 /// Extension trait for CAN controller
@@ -42,35 +60,119 @@ where
     Self: rcc::Instance,
     Can<Self>: fdcan::Instance,
 {
-    fn fdcan<TX, RX>(
-        self,
-        _tx: TX,
-        _rx: RX,
-        rcc: &Rcc,
-    ) -> fdcan::FdCan<Can<Self>, fdcan::ConfigMode>
+    /// Creates the CAN interface, see [`FdCan::new`]
+    fn fdcan<TX, RX>(self, tx: TX, rx: RX, rcc: &Rcc) -> FdCan<Can<Self, (TX, RX)>, ConfigMode>
     where
         TX: sealed::Tx<Self>,
         RX: sealed::Rx<Self>,
+        Can<Self, (TX, RX)>: fdcan::Instance,
     {
-        Self::enable(&rcc.rb);
-
-        if rcc.rb.ccipr.read().fdcansel().is_hse() {
-            // Select P clock as FDCAN clock source
-            rcc.rb.ccipr.modify(|_, w| {
-                // This is sound, as `FdCanClockSource` only contains valid values for this field.
-                unsafe {
-                    w.fdcansel().bits(FdCanClockSource::PCLK as u8);
-                }
-
-                w
-            });
-        }
-
-        self.fdcan_unchecked()
+        FdCan::new(
+            Can {
+                rb: self,
+                pins: (tx, rx),
+            },
+            rcc,
+        )
+        .into_config_mode()
     }
 
-    fn fdcan_unchecked(self) -> fdcan::FdCan<Can<Self>, fdcan::ConfigMode>;
+    /// Creates the CAN interface without taking ownership of any pins
+    ///
+    /// The peripheral clock is not enabled, and nothing prevents the GPIOs used by the
+    /// peripheral from being reconfigured elsewhere while it is active.
+    fn fdcan_unchecked(self) -> FdCan<Can<Self>, ConfigMode>;
 }
+
+/// Resets all FDCAN instances through the RCC and returns them in `PoweredDownMode`
+///
+/// This is a heavy hammer to recover a wedged controller in the field: all configuration,
+/// filters and pending frames are dropped, so `into_config_mode` has to be called and the
+/// configuration applied again. All FDCAN instances share a single reset bit in APB1RSTR1,
+/// along with the clock divider, so every instance of the device has to be handed in, in
+/// whichever mode it is.
+#[cfg(not(any(
+    feature = "stm32g471",
+    feature = "stm32g473",
+    feature = "stm32g474",
+    feature = "stm32g483",
+    feature = "stm32g484",
+    feature = "stm32g491",
+    feature = "stm32g4A1",
+)))]
+pub fn reset_all<P1, M1>(
+    can1: FdCan<Can<crate::stm32::FDCAN1, P1>, M1>,
+    rcc: &Rcc,
+) -> FdCan<Can<crate::stm32::FDCAN1, P1>, PoweredDownMode> {
+    fdcan::reset_peripherals(rcc);
+    can1.into_reset()
+}
+
+/// Resets all FDCAN instances through the RCC and returns them in `PoweredDownMode`
+///
+/// This is a heavy hammer to recover a wedged controller in the field: all configuration,
+/// filters and pending frames are dropped, so `into_config_mode` has to be called and the
+/// configuration applied again. All FDCAN instances share a single reset bit in APB1RSTR1,
+/// along with the clock divider, so every instance of the device has to be handed in, in
+/// whichever mode it is.
+#[cfg(all(
+    any(feature = "stm32g471", feature = "stm32g491", feature = "stm32g4A1"),
+    not(any(
+        feature = "stm32g473",
+        feature = "stm32g474",
+        feature = "stm32g483",
+        feature = "stm32g484",
+    ))
+))]
+#[allow(clippy::type_complexity)]
+pub fn reset_all<P1, M1, P2, M2>(
+    cans: (
+        FdCan<Can<crate::stm32::FDCAN1, P1>, M1>,
+        FdCan<Can<crate::stm32::FDCAN2, P2>, M2>,
+    ),
+    rcc: &Rcc,
+) -> (
+    FdCan<Can<crate::stm32::FDCAN1, P1>, PoweredDownMode>,
+    FdCan<Can<crate::stm32::FDCAN2, P2>, PoweredDownMode>,
+) {
+    fdcan::reset_peripherals(rcc);
+    (cans.0.into_reset(), cans.1.into_reset())
+}
+
+/// Resets all FDCAN instances through the RCC and returns them in `PoweredDownMode`
+///
+/// This is a heavy hammer to recover a wedged controller in the field: all configuration,
+/// filters and pending frames are dropped, so `into_config_mode` has to be called and the
+/// configuration applied again. All FDCAN instances share a single reset bit in APB1RSTR1,
+/// along with the clock divider, so every instance of the device has to be handed in, in
+/// whichever mode it is.
+#[cfg(any(
+    feature = "stm32g473",
+    feature = "stm32g474",
+    feature = "stm32g483",
+    feature = "stm32g484",
+))]
+#[allow(clippy::type_complexity)]
+pub fn reset_all<P1, M1, P2, M2, P3, M3>(
+    cans: (
+        FdCan<Can<crate::stm32::FDCAN1, P1>, M1>,
+        FdCan<Can<crate::stm32::FDCAN2, P2>, M2>,
+        FdCan<Can<crate::stm32::FDCAN3, P3>, M3>,
+    ),
+    rcc: &Rcc,
+) -> (
+    FdCan<Can<crate::stm32::FDCAN1, P1>, PoweredDownMode>,
+    FdCan<Can<crate::stm32::FDCAN2, P2>, PoweredDownMode>,
+    FdCan<Can<crate::stm32::FDCAN3, P3>, PoweredDownMode>,
+) {
+    fdcan::reset_peripherals(rcc);
+    (
+        cans.0.into_reset(),
+        cans.1.into_reset(),
+        cans.2.into_reset(),
+    )
+}
+
 /// Implements sealed::{Tx,Rx} for pins associated with a CAN peripheral
 macro_rules! pins {
     ($PER:ident =>
@@ -87,9 +189,22 @@ macro_rules! pins {
     };
 }
 
+/// Implements the FdCAN instance traits for a CAN peripheral
+macro_rules! instance {
+    ($PER:ident, $n:literal) => {
+        unsafe impl<PINS> fdcan::Instance for Can<$PER, PINS> {
+            const REGISTERS: *mut crate::stm32::fdcan::RegisterBlock = $PER::ptr() as *mut _;
+        }
+        unsafe impl<PINS> message_ram::MsgRamExt for Can<$PER, PINS> {
+            const MSG_RAM: *mut message_ram::RegisterBlock = message_ram::instance_msg_ram($n);
+        }
+    };
+}
+
 mod fdcan1 {
     use super::sealed;
     use super::{Can, CanExt};
+    use crate::fdcan::{self, message_ram, ConfigMode, FdCan};
     use crate::gpio::{
         gpioa::{PA11, PA12},
         gpiob::{PB8, PB9},
@@ -97,7 +212,23 @@ mod fdcan1 {
         AF9,
     };
     use crate::stm32::FDCAN1;
-    use fdcan;
+
+    // The owned halves of `split` must be movable into interrupts
+    static_assertions::assert_impl_all!(
+        fdcan::Tx<Can<FDCAN1>, fdcan::NormalOperationMode>: Send
+    );
+    static_assertions::assert_impl_all!(
+        fdcan::Rx<Can<FDCAN1>, fdcan::NormalOperationMode, fdcan::Fifo0>: Send
+    );
+    static_assertions::assert_impl_all!(
+        fdcan::Rx<Can<FDCAN1>, fdcan::NormalOperationMode, fdcan::Fifo1>: Send
+    );
+    static_assertions::assert_impl_all!(
+        fdcan::FdCanControl<Can<FDCAN1>, fdcan::NormalOperationMode>: Send
+    );
+    static_assertions::assert_impl_all!(
+        fdcan::FdCanRxControl<Can<FDCAN1>, fdcan::BusMonitoringMode>: Send
+    );
 
     // All STM32G4 models with CAN support these pins
     pins! {
@@ -116,21 +247,16 @@ mod fdcan1 {
     }
 
     impl Can<FDCAN1> {
-        pub fn fdcan1(rb: FDCAN1) -> fdcan::FdCan<Self, fdcan::ConfigMode> {
-            fdcan::FdCan::new(Self { rb }).into_config_mode()
+        pub fn fdcan1(rb: FDCAN1) -> FdCan<Self, ConfigMode> {
+            FdCan::new_unchecked(Self { rb, pins: () }).into_config_mode()
         }
     }
     impl CanExt for FDCAN1 {
-        fn fdcan_unchecked(self) -> fdcan::FdCan<Can<Self>, fdcan::ConfigMode> {
+        fn fdcan_unchecked(self) -> FdCan<Can<Self>, ConfigMode> {
             Can::fdcan1(self)
         }
     }
-    unsafe impl fdcan::Instance for Can<FDCAN1> {
-        const REGISTERS: *mut fdcan::RegisterBlock = FDCAN1::ptr() as *mut _;
-    }
-    unsafe impl fdcan::message_ram::Instance for Can<FDCAN1> {
-        const MSG_RAM: *mut fdcan::message_ram::RegisterBlock = (0x4000_a400 as *mut _);
-    }
+    instance!(FDCAN1, 0);
 }
 
 #[cfg(any(
@@ -145,13 +271,12 @@ mod fdcan1 {
 mod fdcan2 {
     use super::sealed;
     use super::{Can, CanExt};
+    use crate::fdcan::{self, message_ram, ConfigMode, FdCan};
     use crate::gpio::{
         gpiob::{PB12, PB13, PB5, PB6},
         AF9,
     };
     use crate::stm32::FDCAN2;
-    use fdcan;
-    use fdcan::message_ram;
 
     pins! {
         FDCAN2 => (
@@ -166,21 +291,16 @@ mod fdcan2 {
     }
 
     impl Can<FDCAN2> {
-        pub fn fdcan2(rb: FDCAN2) -> fdcan::FdCan<Self, fdcan::ConfigMode> {
-            fdcan::FdCan::new(Self { rb }).into_config_mode()
+        pub fn fdcan2(rb: FDCAN2) -> FdCan<Self, ConfigMode> {
+            FdCan::new_unchecked(Self { rb, pins: () }).into_config_mode()
         }
     }
     impl CanExt for FDCAN2 {
-        fn fdcan_unchecked(self) -> fdcan::FdCan<Can<Self>, fdcan::ConfigMode> {
+        fn fdcan_unchecked(self) -> FdCan<Can<Self>, ConfigMode> {
             Can::fdcan2(self)
         }
     }
-    unsafe impl fdcan::Instance for Can<FDCAN2> {
-        const REGISTERS: *mut fdcan::RegisterBlock = FDCAN2::ptr() as *mut _;
-    }
-    unsafe impl fdcan::message_ram::Instance for Can<FDCAN2> {
-        const MSG_RAM: *mut message_ram::RegisterBlock = (0x4000_a750 as *mut _);
-    }
+    instance!(FDCAN2, 1);
 }
 
 #[cfg(any(
@@ -192,14 +312,13 @@ mod fdcan2 {
 mod fdcan3 {
     use super::sealed;
     use super::{Can, CanExt};
+    use crate::fdcan::{self, message_ram, ConfigMode, FdCan};
     use crate::gpio::{
         gpioa::{PA15, PA8},
         gpiob::{PB3, PB4},
         AF11,
     };
     use crate::stm32::FDCAN3;
-    use fdcan;
-    use fdcan::message_ram;
 
     pins! {
         FDCAN3 => (
@@ -214,19 +333,14 @@ mod fdcan3 {
     }
 
     impl Can<FDCAN3> {
-        pub fn fdcan3(rb: FDCAN3) -> fdcan::FdCan<Self, fdcan::ConfigMode> {
-            fdcan::FdCan::new(Self { rb }).into_config_mode()
+        pub fn fdcan3(rb: FDCAN3) -> FdCan<Self, ConfigMode> {
+            FdCan::new_unchecked(Self { rb, pins: () }).into_config_mode()
         }
     }
     impl CanExt for FDCAN3 {
-        fn fdcan_unchecked(self) -> fdcan::FdCan<Can<Self>, fdcan::ConfigMode> {
+        fn fdcan_unchecked(self) -> FdCan<Can<Self>, ConfigMode> {
             Can::fdcan3(self)
         }
     }
-    unsafe impl fdcan::Instance for Can<FDCAN3> {
-        const REGISTERS: *mut fdcan::RegisterBlock = FDCAN3::ptr() as *mut _;
-    }
-    unsafe impl fdcan::message_ram::Instance for Can<FDCAN3> {
-        const MSG_RAM: *mut message_ram::RegisterBlock = (0x4000_aaa0 as *mut _);
-    }
+    instance!(FDCAN3, 2);
 }
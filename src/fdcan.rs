@@ -2,10 +2,13 @@
 
 //! FdCAN Operations
 
+/// Estimation of the bus load
+pub mod bus_load;
+/// bxcan-like adapter for code migrating from the F-series HALs
+#[cfg(feature = "bxcan-compat")]
+pub mod bxcan_compat;
 /// Configuration of an FdCAN instance
 pub mod config;
-#[cfg(feature = "embedded-can-03")]
-mod embedded_can;
 /// Filtering of CAN Messages
 pub mod filter;
 /// Header and info of transmitted and receiving frames
@@ -14,23 +17,33 @@ pub mod frame;
 pub mod id;
 /// Interrupt Line Information
 pub mod interrupt;
-mod message_ram;
+/// SAE J1939 identifiers
+#[cfg(feature = "j1939")]
+pub mod j1939;
+pub(crate) mod message_ram;
+/// Hooks to trace transmitted and received frames
+#[cfg(feature = "trace")]
+pub mod trace;
 
 use id::{Id, IdReg};
 
-use crate::rcc::Rcc;
+use crate::rcc::{Enable, Rcc, Reset};
 use crate::stm32::fdcan::RegisterBlock;
+use crate::stm32::TIM3;
 use config::{
-    ClockDivider, DataBitTiming, FdCanConfig, FrameTransmissionConfig, GlobalFilter,
-    NominalBitTiming, TimestampSource,
+    BitTimingError, ClockDivider, DataBitTiming, FdCanConfig, FrameTransmissionConfig,
+    GlobalFilter, NominalBitTiming, RxFifoMode, TimestampSource,
 };
 use filter::{
-    ActivateFilter as _, ExtendedFilter, ExtendedFilterSlot, StandardFilter, StandardFilterSlot,
-    EXTENDED_FILTER_MAX, STANDARD_FILTER_MAX,
+    Action, ActivateFilter, ExtendedFilter, ExtendedFilterSlot, FilterTarget, StandardFilter,
+    StandardFilterSlot, TooManyFilters, EXTENDED_FILTER_MAX, STANDARD_FILTER_MAX,
 };
 use frame::MergeTxFrameHeader;
-use frame::{RxFrameInfo, TxFrameHeader};
-use interrupt::{Interrupt, InterruptLine, Interrupts};
+use frame::{
+    clear_padding, len_to_dlc, pack_words, FrameError, FrameFormat, ReceivedFrame, RxFrameInfo,
+    TxEvent, TxFrameHeader,
+};
+use interrupt::{Interrupt, InterruptLine, InterruptLineConfig, Interrupts};
 
 use message_ram::MsgRamExt;
 use message_ram::RxFifoElement;
@@ -39,14 +52,11 @@ use core::cmp::Ord;
 use core::convert::Infallible;
 use core::convert::TryFrom;
 use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
 use core::ptr::NonNull;
-
-mod sealed {
-    /// A TX pin configured for CAN communication
-    pub trait Tx<CAN> {}
-    /// An RX pin configured for CAN communication
-    pub trait Rx<CAN> {}
-}
+use core::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+#[cfg(feature = "async")]
+use core::task::Poll;
 
 /// An FdCAN peripheral instance.
 ///
@@ -61,11 +71,151 @@ mod sealed {
 ///   register block.
 /// * `REGISTERS` is a pointer to that peripheral's register block and can be safely accessed for as
 ///   long as ownership or a borrow of the implementing type is present.
-pub unsafe trait Instance: MsgRamExt + crate::rcc::Instance {
+pub unsafe trait Instance: MsgRamExt {
     /// Pointer to the instance's register block.
     const REGISTERS: *mut RegisterBlock;
 }
 
+/// Returns the index of instance `I`: 0 for FDCAN1, 1 for FDCAN2 and 2 for FDCAN3
+///
+/// The register blocks of the instances directly follow each other, 0x400 bytes apart.
+fn instance_index<I: Instance>() -> usize {
+    (I::REGISTERS as usize - crate::stm32::FDCAN1::ptr() as usize) / 0x400
+}
+
+/// Returns the NVIC interrupt vector which is triggered by `line` of instance `I`.
+///
+/// Use this together with `cortex_m::peripheral::NVIC::unmask` after routing interrupts to
+/// a line with `set_interrupt_line_config` and enabling it with `enable_interrupt_line`.
+pub fn interrupt_line_vector<I: Instance>(line: InterruptLine) -> crate::stm32::Interrupt {
+    use crate::stm32::Interrupt::*;
+
+    match (instance_index::<I>(), line) {
+        (0, InterruptLine::_0) => FDCAN1_INTR0_IT,
+        (0, InterruptLine::_1) => FDCAN1_INTR1_IT,
+        #[cfg(any(
+            feature = "stm32g471",
+            feature = "stm32g473",
+            feature = "stm32g474",
+            feature = "stm32g483",
+            feature = "stm32g484",
+            feature = "stm32g491",
+            feature = "stm32g4A1",
+        ))]
+        (1, InterruptLine::_0) => FDCAN2_INTR0_IT,
+        #[cfg(any(
+            feature = "stm32g471",
+            feature = "stm32g473",
+            feature = "stm32g474",
+            feature = "stm32g483",
+            feature = "stm32g484",
+            feature = "stm32g491",
+            feature = "stm32g4A1",
+        ))]
+        (1, InterruptLine::_1) => FDCAN2_INTR1_IT,
+        #[cfg(any(
+            feature = "stm32g473",
+            feature = "stm32g474",
+            feature = "stm32g483",
+            feature = "stm32g484",
+        ))]
+        (2, InterruptLine::_0) => FDCAN3_INTR0_IT,
+        #[cfg(any(
+            feature = "stm32g473",
+            feature = "stm32g474",
+            feature = "stm32g483",
+            feature = "stm32g484",
+        ))]
+        (2, InterruptLine::_1) => FDCAN3_INTR1_IT,
+        // `can` only implements `Instance` for the instances of the selected device
+        _ => unreachable!(),
+    }
+}
+
+/// Resets all FDCAN instances through the RCC, see [`crate::can::reset_all`]
+pub(crate) fn reset_peripherals(rcc: &Rcc) {
+    FdCanRcc::reset(&rcc.rb);
+    // The divider is back at its reset value, which the instances have to configure again
+    CKDIV_USERS.store(0, Ordering::Relaxed);
+    CKDIV_SHARED.store(ClockDivider::_1 as u8, Ordering::Relaxed);
+}
+
+/// All instances share one clock enable and one reset bit in the RCC, reached through FDCAN1
+/// as every STM32G4 with CAN has it
+type FdCanRcc = crate::stm32::FDCAN1;
+
+/// Instances which configured the shared clock divider, bit `n - 1` for FDCAN`n`
+static CKDIV_USERS: AtomicU8 = AtomicU8::new(0);
+/// Clock divider configured by the instances in `CKDIV_USERS`
+static CKDIV_SHARED: AtomicU8 = AtomicU8::new(ClockDivider::_1 as u8);
+
+/// Receives which reported an overrun, one counter per instance, see
+/// [`FdCanControl::dropped_frame_count`]
+static DROPPED_FRAMES: [AtomicU32; 3] = [AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0)];
+
+/// Returns the counter of lost frames of instance `I`
+fn dropped_frames<I: Instance>() -> &'static AtomicU32 {
+    &DROPPED_FRAMES[instance_index::<I>()]
+}
+
+/// Wakers of the tasks waiting in [`Rx::receive_async`], two per instance, one for each line
+#[cfg(feature = "async")]
+static WAKERS: [atomic_waker::AtomicWaker; 6] = [
+    atomic_waker::AtomicWaker::new(),
+    atomic_waker::AtomicWaker::new(),
+    atomic_waker::AtomicWaker::new(),
+    atomic_waker::AtomicWaker::new(),
+    atomic_waker::AtomicWaker::new(),
+    atomic_waker::AtomicWaker::new(),
+];
+
+/// Returns the waker for `line` of instance `I`
+#[cfg(feature = "async")]
+fn waker<I: Instance>(line: InterruptLine) -> &'static atomic_waker::AtomicWaker {
+    &WAKERS[instance_index::<I>() * 2 + line as usize]
+}
+
+/// Wakes the tasks waiting in [`Rx::receive_async`] on `line` of instance `I`.
+///
+/// Call this from the interrupt service routine of every line an Rx FIFO new message interrupt
+/// is routed to, passing the instance and line of that vector:
+///
+/// | Vector            | Call                                             |
+/// |-------------------|--------------------------------------------------|
+/// | `FDCAN1_INTR0_IT` | `on_interrupt::<Can<FDCAN1>>(InterruptLine::_0)` |
+/// | `FDCAN1_INTR1_IT` | `on_interrupt::<Can<FDCAN1>>(InterruptLine::_1)` |
+/// | `FDCAN2_INTR0_IT` | `on_interrupt::<Can<FDCAN2>>(InterruptLine::_0)` |
+/// | `FDCAN2_INTR1_IT` | `on_interrupt::<Can<FDCAN2>>(InterruptLine::_1)` |
+/// | `FDCAN3_INTR0_IT` | `on_interrupt::<Can<FDCAN3>>(InterruptLine::_0)` |
+/// | `FDCAN3_INTR1_IT` | `on_interrupt::<Can<FDCAN3>>(InterruptLine::_1)` |
+///
+/// `Can` is [`crate::can::Can`], the pins it holds do not matter here.
+/// [`interrupt_line_vector`] gives the same mapping at runtime. This clears the new
+/// message flags of the FIFOs routed to `line`, other flags are left for the caller to handle.
+#[cfg(feature = "async")]
+pub fn on_interrupt<I: Instance>(line: InterruptLine) {
+    // Safety: Read of the line selection and write-1-to-clear of the new message flags only.
+    let can = unsafe { &*I::REGISTERS };
+    let ils = InterruptLineConfig::from_bits(can.ils.read().bits());
+    let flags = [Interrupt::RxFifo0NewMsg, Interrupt::RxFifo1NewMsg]
+        .iter()
+        .filter(|&&interrupt| ils.line(interrupt) == line)
+        .fold(0, |acc, &interrupt| acc | interrupt as u32);
+    can.ir.write(|w| unsafe { w.bits(flags) });
+
+    waker::<I>(line).wake();
+}
+
+/// Wakes the tasks waiting in [`Rx::receive_async`] on instance `I`, on either line.
+///
+/// This is [`on_interrupt`] for both lines, for applications which handle both lines of an
+/// instance in a single routine.
+#[cfg(feature = "async")]
+pub fn interrupt_handler<I: Instance>() {
+    on_interrupt::<I>(InterruptLine::_0);
+    on_interrupt::<I>(InterruptLine::_1);
+}
+
 /// Indicates if an Receive Overflow has occurred
 #[derive(Clone, Copy, Debug)]
 #[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
@@ -88,6 +238,18 @@ pub struct ErrorCounters {
     /// Transmit CAN error counter
     transmit_err: u8,
 }
+impl ErrorCounters {
+    fn from_ecr(ecr: &crate::stm32::fdcan::ecr::R) -> Self {
+        ErrorCounters {
+            can_errors: ecr.cel().bits(),
+            transmit_err: ecr.tec().bits(),
+            receive_err: match ecr.rp().bit() {
+                false => ReceiveErrorOverflow::Normal(ecr.rec().bits()),
+                true => ReceiveErrorOverflow::Overflow(ecr.rec().bits()),
+            },
+        }
+    }
+}
 
 /// Loopback Mode
 #[derive(Clone, Copy, Debug)]
@@ -162,6 +324,288 @@ impl TryFrom<u8> for LastErrorCode {
     }
 }
 
+/// Errors which can occur on the CAN bus
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum CanError {
+    /// The node is in the Bus_Off state
+    BusOff,
+    /// More than 5 equal bits in sequence have been detected
+    Stuff,
+    /// A fixed format part of a received frame had the wrong format
+    Form,
+    /// A transmitted message was not acknowledged by another node
+    Ack,
+    /// A transmitted bit was monitored with the opposite level
+    Bit,
+    /// The CRC checksum of a received message was incorrect
+    Crc,
+}
+impl CanError {
+    fn from_lec(lec: LastErrorCode) -> Option<Self> {
+        match lec {
+            LastErrorCode::NoError | LastErrorCode::NoChange => None,
+            LastErrorCode::StuffError => Some(Self::Stuff),
+            LastErrorCode::FormError => Some(Self::Form),
+            LastErrorCode::AckError => Some(Self::Ack),
+            LastErrorCode::Bit1Error | LastErrorCode::Bit0Error => Some(Self::Bit),
+            LastErrorCode::CRCError => Some(Self::Crc),
+        }
+    }
+}
+impl core::fmt::Display for CanError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::BusOff => "bus off",
+            Self::Stuff => "stuff error",
+            Self::Form => "form error",
+            Self::Ack => "acknowledge error",
+            Self::Bit => "bit error",
+            Self::Crc => "CRC error",
+        })
+    }
+}
+#[cfg(feature = "core-error")]
+impl core::error::Error for CanError {}
+
+/// Counts of the bus errors by type, built up from a history of last error codes
+///
+/// The hardware only holds the last error code, see [`ProtocolStatus::last_error`]. Call
+/// [`ErrorHistogram::record`] periodically or from the protocol error interrupts to keep
+/// track of which errors occur on the bus. The counters saturate.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct ErrorHistogram {
+    /// Number of stuff errors
+    pub stuff: u32,
+    /// Number of form errors
+    pub form: u32,
+    /// Number of acknowledge errors
+    pub ack: u32,
+    /// Number of bit errors where a recessive bit was monitored as dominant
+    pub bit1: u32,
+    /// Number of bit errors where a dominant bit was monitored as recessive
+    pub bit0: u32,
+    /// Number of CRC errors
+    pub crc: u32,
+}
+impl ErrorHistogram {
+    /// Counts the error reported by `lec`, `NoError` and `NoChange` are ignored
+    pub fn record(&mut self, lec: LastErrorCode) {
+        let counter = match lec {
+            LastErrorCode::NoError | LastErrorCode::NoChange => return,
+            LastErrorCode::StuffError => &mut self.stuff,
+            LastErrorCode::FormError => &mut self.form,
+            LastErrorCode::AckError => &mut self.ack,
+            LastErrorCode::Bit1Error => &mut self.bit1,
+            LastErrorCode::Bit0Error => &mut self.bit0,
+            LastErrorCode::CRCError => &mut self.crc,
+        };
+        *counter = counter.saturating_add(1);
+    }
+
+    /// Returns the total number of recorded errors
+    pub fn total(&self) -> u32 {
+        [
+            self.stuff, self.form, self.ack, self.bit1, self.bit0, self.crc,
+        ]
+        .iter()
+        .fold(0, |sum, n| sum.saturating_add(*n))
+    }
+}
+
+/// Errors reported by [`FdCan::selftest`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum SelfTestError {
+    /// The payload length is not a valid CAN FD payload size
+    InvalidLength,
+    /// The frame was rejected for transmission, for example as the `FrameTransmissionConfig`
+    /// does not allow it
+    Frame(FrameError),
+    /// The frame could not be queued for transmission in time
+    TransmitTimeout,
+    /// The frame was not received back in time
+    ReceiveTimeout,
+    /// A frame was received back, but its id or payload differ from what was transmitted
+    Mismatch,
+}
+impl core::fmt::Display for SelfTestError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidLength => f.write_str("invalid payload length"),
+            Self::Frame(e) => write!(f, "invalid frame: {}", e),
+            Self::TransmitTimeout => f.write_str("timeout while queueing the frame"),
+            Self::ReceiveTimeout => {
+                f.write_str("timeout while waiting for the frame to be received")
+            }
+            Self::Mismatch => f.write_str("received frame differs from the transmitted frame"),
+        }
+    }
+}
+#[cfg(feature = "core-error")]
+impl core::error::Error for SelfTestError {}
+
+/// Error returned by [`FdCan::abort_timeout`] when the hardware did not respond in time
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct Timeout;
+impl core::fmt::Display for Timeout {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("timeout while waiting for the peripheral")
+    }
+}
+#[cfg(feature = "core-error")]
+impl core::error::Error for Timeout {}
+
+/// Error returned by the `into_*_timeout` mode transitions, naming the request the
+/// peripheral did not acknowledge in time
+///
+/// This points at a missing peripheral clock or a misconfigured bus; the instances can be reset
+/// with [`reset_all`](crate::can::reset_all) to recover. The `*_timeout` mode transitions are
+/// recommended over the `into_*` ones, which spin forever in that case.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum ModeError {
+    /// CCCR.INIT did not follow the request to enter or leave init mode
+    InitTimeout,
+    /// CCCR.CSA did not follow the request to enter or leave power down mode
+    PowerDownTimeout,
+}
+impl core::fmt::Display for ModeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::InitTimeout => "timeout while switching init mode",
+            Self::PowerDownTimeout => "timeout while switching power down mode",
+        })
+    }
+}
+#[cfg(feature = "core-error")]
+impl core::error::Error for ModeError {}
+
+/// Returns the number of transmit mailboxes without a pending request in TXBRP
+#[inline]
+fn free_tx_slots(trp: u8) -> u8 {
+    message_ram::TX_FIFO_MAX - (trp & 0b111).count_ones() as u8
+}
+
+/// Polls `done` until it returns `true`, at most `spins` times when a limit is given.
+#[inline]
+fn spin_until(spins: Option<u32>, mut done: impl FnMut() -> bool) -> Result<(), Timeout> {
+    match spins {
+        None => {
+            while !done() {}
+            Ok(())
+        }
+        Some(spins) if (0..spins).any(|_| done()) => Ok(()),
+        Some(_) => Err(Timeout),
+    }
+}
+
+/// Errors reported by [`FdCan::try_new`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum InitError {
+    /// The endianness register (ENDN) did not read the expected `0x87654321`
+    ///
+    /// This usually means the peripheral is not clocked or held in reset.
+    EndiannessCheckFailed,
+}
+impl core::fmt::Display for InitError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::EndiannessCheckFailed => {
+                "endianness check failed, is the peripheral clock enabled?"
+            }
+        })
+    }
+}
+#[cfg(feature = "core-error")]
+impl core::error::Error for InitError {}
+
+/// Error returned when a configuration can not be applied
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum ConfigError {
+    /// Another instance which has not been freed since uses a different clock divider
+    ///
+    /// The divider is shared by all instances, see [`FdCan::set_clock_divider`].
+    ClockDividerInUse,
+}
+impl core::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::ClockDividerInUse => "clock divider is in use by another instance",
+        })
+    }
+}
+#[cfg(feature = "core-error")]
+impl core::error::Error for ConfigError {}
+
+/// Error returned by [`Tx::try_transmit`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum TransmitError {
+    /// All transmit mailboxes hold frames with a higher or equal priority
+    Full,
+    /// The header does not describe a valid frame, see [`TxFrameHeader::validate`]
+    InvalidFrame(FrameError),
+}
+impl core::fmt::Display for TransmitError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Full => f.write_str("transmit queue is full"),
+            Self::InvalidFrame(e) => write!(f, "invalid frame: {}", e),
+        }
+    }
+}
+#[cfg(feature = "core-error")]
+impl core::error::Error for TransmitError {}
+
+/// Error returned by [`Tx::transmit_all`] when a frame of the batch is not valid
+///
+/// The frames before `index` have been queued, the remainder has not.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct BatchError {
+    /// Index of the first invalid frame in the batch
+    pub index: usize,
+    /// Why the frame is not valid
+    pub error: FrameError,
+}
+impl core::fmt::Display for BatchError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid frame at index {}: {}", self.index, self.error)
+    }
+}
+#[cfg(feature = "core-error")]
+impl core::error::Error for BatchError {}
+
+/// Error returned by [`Rx::receive_into`] when the buffer can not hold the payload of the frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct RxBufTooSmall {
+    /// Length of the payload in bytes
+    pub required: usize,
+}
+impl core::fmt::Display for RxBufTooSmall {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "buffer too small, {} bytes required", self.required)
+    }
+}
+#[cfg(feature = "core-error")]
+impl core::error::Error for RxBufTooSmall {}
+
+/// Outcome of a successful [`Tx::try_transmit`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum TransmitOutcome {
+    /// The frame was placed in a free mailbox
+    Queued,
+    /// A pending frame with a lower priority was discarded to make room for the frame
+    Displaced,
+}
+
 /// Some status indications regarding the FDCAN protocl
 #[derive(Clone, Copy, Debug)]
 #[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
@@ -178,6 +622,120 @@ pub struct ProtocolStatus {
     error_passive_state: bool,
     /// Indicates te last type of error which occurred on the CAN bus.
     last_error: LastErrorCode,
+    /// A protocol exception event occurred since the status was read last
+    protocol_exception: bool,
+}
+impl ProtocolStatus {
+    fn from_psr(psr: &crate::stm32::fdcan::psr::R) -> Self {
+        ProtocolStatus {
+            activity: Activity::try_from(0 /*psr.act().bits()*/).unwrap(), //TODO: stm32g4 does not allow reading from this register
+            transmitter_delay_comp: psr.tdcv().bits(),
+            bus_off_status: psr.bo().bit_is_set(),
+            error_warning: psr.ew().bit_is_set(),
+            error_passive_state: psr.ep().bit_is_set(),
+            last_error: LastErrorCode::try_from(psr.lec().bits()).unwrap(),
+            protocol_exception: psr.pxe().bit_is_set(),
+        }
+    }
+
+    /// Returns the type of the current activity
+    #[inline]
+    pub fn activity(&self) -> Activity {
+        self.activity
+    }
+
+    /// Returns the transmitter delay compensation value
+    #[inline]
+    pub fn transmitter_delay_comp(&self) -> u8 {
+        self.transmitter_delay_comp
+    }
+
+    /// Returns `true` if the peripheral is in the Bus_Off state
+    #[inline]
+    pub fn bus_off_status(&self) -> bool {
+        self.bus_off_status
+    }
+
+    /// Returns `true` if at least one of the error counters reached the warning limit of 96
+    #[inline]
+    pub fn error_warning(&self) -> bool {
+        self.error_warning
+    }
+
+    /// Returns `true` if the peripheral is error passive
+    #[inline]
+    pub fn error_passive_state(&self) -> bool {
+        self.error_passive_state
+    }
+
+    /// Returns the type of the last error which occurred on the CAN bus
+    #[inline]
+    pub fn last_error(&self) -> LastErrorCode {
+        self.last_error
+    }
+
+    /// Returns `true` if a protocol exception event occurred since the status was read last,
+    /// see [`FdCanControl::protocol_exception_occurred`]
+    #[inline]
+    pub fn protocol_exception(&self) -> bool {
+        self.protocol_exception
+    }
+}
+
+/// Snapshot of the state of an FdCan instance, see [`FdCanControl::status`]
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct CanStatus {
+    /// No frame is pending for transmission
+    pub tx_idle: bool,
+    /// Number of free transmit mailboxes
+    pub tx_free_level: u8,
+    /// Number of frames in Rx FIFO 0
+    pub rx0_fill_level: u8,
+    /// Number of frames in Rx FIFO 1
+    pub rx1_fill_level: u8,
+    /// Error counters
+    pub error_counters: ErrorCounters,
+    /// Protocol status
+    pub protocol_status: ProtocolStatus,
+}
+
+/// Indicates where a High Priority Message has been stored
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum MessageStorage {
+    /// No FIFO selected
+    NoFifo = 0b00,
+    /// The message has been lost because the FIFO was full
+    FifoMessageLost = 0b01,
+    /// The message has been stored in FIFO 0
+    Fifo0 = 0b10,
+    /// The message has been stored in FIFO 1
+    Fifo1 = 0b11,
+}
+impl From<u8> for MessageStorage {
+    fn from(value: u8) -> Self {
+        match value & 0b11 {
+            0b00 => Self::NoFifo,
+            0b01 => Self::FifoMessageLost,
+            0b10 => Self::Fifo0,
+            _ => Self::Fifo1,
+        }
+    }
+}
+
+/// Status of the last received High Priority Message
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct HpmStatus {
+    /// Index of the filter which flagged the message
+    pub filter_index: u8,
+    /// Set if `filter_index` refers to an extended filter instead of a standard filter
+    pub extended_filter: bool,
+    /// Where the message has been stored
+    pub storage: MessageStorage,
+    /// Index of the message in the Rx FIFO it was stored in
+    pub buffer_index: u8,
 }
 
 /// Allows for Transmit Operations
@@ -188,6 +746,11 @@ pub trait Receive {}
 /// Allows for the FdCan Instance to be released or to enter ConfigMode
 pub struct PoweredDownMode;
 /// Allows for the configuration for the Instance
+///
+/// Setters which write registers that are only writable during initialization, such as the
+/// bit timings, are only implemented for this mode. Once the peripheral left it, methods like
+/// `set_nominal_bit_timing` and `apply_config` do not exist on the `FdCan`, so calling them is a
+/// compile error instead of a write the hardware ignores.
 pub struct ConfigMode;
 /// This mode can be used for a “Hot Selftest”, meaning the FDCAN can be tested without
 /// affecting a running CAN system connected to the FDCAN_TX and FDCAN_RX pins. In this
@@ -214,7 +777,14 @@ impl Receive for NormalOperationMode {}
 /// frames, or overload frames. In case of an error condition or overload condition, it does not
 /// send dominant bits, instead it waits for the occurrence of bus idle condition to resynchronize
 /// itself to the CAN communication. The error counters for transmit and receive are frozen while
-/// error logging (can_errors) is active. TODO: automatically enter in this mode?
+/// error logging (can_errors) is active.
+///
+/// The hardware enters restricted operation by itself when the Tx handler could not read a
+/// frame from the message RAM in time, see [`FdCan::is_restricted`]. With
+/// [`FdCanConfig::auto_restricted_on_error`] set, [`FdCan::restrict_on_error`] does the same
+/// after protocol errors. Transmission is not possible in this mode, so it can not be `split`.
+/// The node resumes normal transmission only after returning through ConfigMode, with
+/// `into_config_mode` followed by `into_normal`.
 pub struct RestrictedOperationMode;
 impl Receive for RestrictedOperationMode {}
 ///  In Bus monitoring mode (for more details refer to ISO11898-1, 10.12 Bus monitoring),
@@ -246,6 +816,7 @@ where
             control: FdCanControl {
                 config,
                 instance,
+                receive_paused: false,
                 _mode: core::marker::PhantomData,
             },
         }
@@ -256,6 +827,7 @@ where
             control: FdCanControl {
                 config: self.control.config,
                 instance: self.control.instance,
+                receive_paused: self.control.receive_paused,
                 _mode: core::marker::PhantomData,
             },
         }
@@ -269,11 +841,21 @@ where
         &mut self.control.instance
     }
 
+    /// Returns this instance in `PoweredDownMode`, after `reset_peripherals` reset it
+    pub(crate) fn into_reset(self) -> FdCan<I, PoweredDownMode> {
+        Self::create_can(FdCanConfig::default(), self.control.instance)
+    }
+
     #[inline]
     fn registers(&self) -> &RegisterBlock {
         unsafe { &*I::REGISTERS }
     }
 
+    #[inline]
+    fn msg_ram(&self) -> &message_ram::RegisterBlock {
+        unsafe { &*I::MSG_RAM }
+    }
+
     #[inline]
     fn msg_ram_mut(&mut self) -> &mut message_ram::RegisterBlock {
         self.instance().msg_ram_mut()
@@ -286,11 +868,85 @@ where
 
     #[inline]
     fn enter_init_mode(&mut self) {
+        // Without a limit this can not time out
+        self.enter_init_mode_within(None).unwrap_or(());
+    }
+
+    #[inline]
+    fn enter_init_mode_within(&mut self, spins: Option<u32>) -> Result<(), ModeError> {
         let can = self.registers();
 
         can.cccr.modify(|_, w| w.init().set_bit());
-        while can.cccr.read().init().bit_is_clear() {}
+        spin_until(spins, || can.cccr.read().init().bit_is_set())
+            .map_err(|Timeout| ModeError::InitTimeout)?;
         can.cccr.modify(|_, w| w.cce().set_bit());
+        Ok(())
+    }
+
+    /// Enters init mode within `spins` polls and moves to `NEW_MODE`, or hands back `self`
+    fn enter_init_mode_timeout<NEW_MODE>(
+        mut self,
+        spins: u32,
+    ) -> Result<FdCan<I, NEW_MODE>, (Self, ModeError)> {
+        match self.enter_init_mode_within(Some(spins)) {
+            Ok(()) => Ok(self.into_can_mode()),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    /// Writes the global filter and the filter list sizes, or rejects all frames while receiving
+    /// is disabled by `set_receive_enabled`
+    ///
+    /// RXGFC can only be written in init mode with CCE set.
+    fn write_rxgfc(&mut self) {
+        let (filter, standard, extended) = if self.control.receive_paused {
+            // Without any filter elements, all frames are handled by the global filter
+            (GlobalFilter::reject_all(), 0, 0)
+        } else {
+            (
+                self.control.config.global_filter,
+                STANDARD_FILTER_MAX,
+                EXTENDED_FILTER_MAX,
+            )
+        };
+        self.registers().rxgfc.modify(|_, w| {
+            unsafe {
+                w.anfs()
+                    .bits(filter.handle_standard_frames as u8)
+                    .anfe()
+                    .bits(filter.handle_extended_frames as u8)
+                    .lss()
+                    .bits(standard)
+                    .lse()
+                    .bits(extended)
+            }
+            .rrfs()
+            .bit(filter.reject_remote_standard_frames)
+            .rrfe()
+            .bit(filter.reject_remote_extended_frames)
+        });
+    }
+
+    /// Enters init mode to change protected registers with `change`, then returns to the
+    /// current mode
+    ///
+    /// The mode bits are left as they are, so the peripheral resumes in the same mode. Setting
+    /// CCE resets the Tx queue and Rx FIFO status.
+    fn reinit_in_place(&mut self, change: impl FnOnce(&mut Self)) {
+        self.enter_init_mode();
+        change(self);
+
+        let can = self.registers();
+        can.cccr.modify(|_, w| w.cce().clear_bit());
+        can.cccr.modify(|_, w| w.init().clear_bit());
+        // Without a limit this can not time out
+        spin_until(None, || can.cccr.read().init().bit_is_clear()).unwrap_or(());
+    }
+
+    /// Returns the number of the FDCAN instance: 1 for FDCAN1, 2 for FDCAN2 and 3 for FDCAN3
+    #[inline]
+    pub fn instance_number(&self) -> u8 {
+        instance_index::<I>() as u8 + 1
     }
 
     /// Returns the current FDCAN config settings
@@ -330,8 +986,12 @@ where
     }
 
     #[inline]
-    fn set_normal_operations(&mut self, _enabled: bool) {
+    fn set_normal_operations(&mut self, enabled: bool) {
         self.set_loopback_mode(LoopbackMode::None);
+        if enabled {
+            // Clears a restricted operation entered by the hardware
+            self.set_restricted_operations(false);
+        }
     }
 
     #[inline]
@@ -342,9 +1002,20 @@ where
 
     #[inline]
     fn set_power_down_mode(&mut self, enabled: bool) {
+        // Without a limit this can not time out
+        self.set_power_down_mode_within(enabled, None).unwrap_or(());
+    }
+
+    #[inline]
+    fn set_power_down_mode_within(
+        &mut self,
+        enabled: bool,
+        spins: Option<u32>,
+    ) -> Result<(), ModeError> {
         let can = self.registers();
         can.cccr.modify(|_, w| w.csr().bit(enabled));
-        while can.cccr.read().csa().bit() != enabled {}
+        spin_until(spins, || can.cccr.read().csa().bit() == enabled)
+            .map_err(|Timeout| ModeError::PowerDownTimeout)
     }
 
     /// Enable/Disable the specific Interrupt Line
@@ -352,12 +1023,19 @@ where
     pub fn enable_interrupt_line(&mut self, line: InterruptLine, enabled: bool) {
         let can = self.registers();
         match line {
-            InterruptLine::_0 => can.ile.modify(|_, w| w.eint1().bit(enabled)),
-            InterruptLine::_1 => can.ile.modify(|_, w| w.eint0().bit(enabled)),
+            InterruptLine::_0 => can.ile.modify(|_, w| w.eint0().bit(enabled)),
+            InterruptLine::_1 => can.ile.modify(|_, w| w.eint1().bit(enabled)),
         }
     }
 
     /// Starts listening for a CAN interrupt.
+    ///
+    /// An interrupt driven transmitter can refill the mailboxes without polling: enable
+    /// `Interrupt::TxEmpty` to be woken once all of them are free, or `Interrupt::TxComplete`
+    /// together with [`Tx::set_completion_interrupt`] to be woken for every transmitted frame.
+    /// `Interrupt::TxEventFull` signals that [`Tx::read_tx_event`] must be called before events
+    /// are lost. As all flags, these stay set until acknowledged with
+    /// [`FdCan::clear_interrupts`].
     #[inline]
     pub fn enable_interrupt(&mut self, interrupt: Interrupt) {
         self.enable_interrupts(Interrupts::from_bits_truncate(interrupt as u32))
@@ -390,12 +1068,97 @@ where
         self.control.error_counters()
     }
 
+    /// Returns `true` if the node is error active, see [`FdCanControl::is_error_active`]
+    #[inline]
+    pub fn is_error_active(&self) -> bool {
+        self.control.is_error_active()
+    }
+
+    /// Returns `true` if the node is in the error warning state
+    #[inline]
+    pub fn is_error_warning(&self) -> bool {
+        self.control.is_error_warning()
+    }
+
+    /// Returns `true` if the node is error passive
+    #[inline]
+    pub fn is_error_passive(&self) -> bool {
+        self.control.is_error_passive()
+    }
+
+    /// Retrieve the status of the last High Priority Message
+    #[inline]
+    pub fn high_priority_message_status(&self) -> HpmStatus {
+        self.control.high_priority_message_status()
+    }
+
+    /// Returns the id of the oldest frame in `fifo`, without releasing it
+    #[inline]
+    pub fn last_rx_id(&self, fifo: Fifo) -> Option<Id> {
+        self.control.last_rx_id(fifo)
+    }
+
+    /// Returns the id of the frame pending for transmission in `mailbox`, if any
+    #[inline]
+    pub fn pending_tx_id(&self, mailbox: Mailbox) -> Option<Id> {
+        self.control.pending_tx_id(mailbox)
+    }
+
+    /// Returns the number of received frames which reported an overrun
+    #[inline]
+    pub fn dropped_frame_count(&self) -> u32 {
+        self.control.dropped_frame_count()
+    }
+
+    /// Resets the counter returned by `dropped_frame_count`
+    #[inline]
+    pub fn reset_dropped_frame_count(&mut self) {
+        self.control.reset_dropped_frame_count()
+    }
+
+    /// Returns `true` if a message RAM access failure occurred
+    #[inline]
+    pub fn message_ram_access_failed(&self) -> bool {
+        self.control.message_ram_access_failed()
+    }
+
+    /// Clears the message RAM access failure flag
+    #[inline]
+    pub fn clear_message_ram_access_failure(&mut self) {
+        self.control.clear_message_ram_access_failure()
+    }
+
+    /// Returns `true` if a protocol exception event occurred since the protocol status was
+    /// read last
+    #[inline]
+    pub fn protocol_exception_occurred(&self) -> bool {
+        self.control.protocol_exception_occurred()
+    }
+
+    /// Clears the protocol exception event flag
+    #[inline]
+    pub fn clear_protocol_exception(&mut self) {
+        self.control.clear_protocol_exception()
+    }
+
     /// Set an Standard Address CAN filter into slot 'id'
     #[inline]
     pub fn set_standard_filter(&mut self, slot: StandardFilterSlot, filter: StandardFilter) {
         self.msg_ram_mut().filters.flssa[slot as usize].activate(filter);
     }
 
+    /// Disable the Standard Address CAN filter in slot 'id'
+    #[inline]
+    pub fn disable_standard_filter(&mut self, slot: StandardFilterSlot) {
+        self.set_standard_filter(slot, StandardFilter::disable());
+    }
+
+    /// Read back the Standard Address CAN filter in slot 'id'
+    #[inline]
+    pub fn get_standard_filter(&self, slot: StandardFilterSlot) -> StandardFilter {
+        ActivateFilter::read(&self.msg_ram().filters.flssa[slot as usize])
+    }
+
     /// Set an array of Standard Address CAN filters and overwrite the current set
     pub fn set_standard_filters(
         &mut self,
@@ -412,6 +1175,18 @@ where
         self.msg_ram_mut().filters.flesa[slot as usize].activate(filter);
     }
 
+    /// Disable the Extended Address CAN filter in slot 'id'
+    #[inline]
+    pub fn disable_extended_filter(&mut self, slot: ExtendedFilterSlot) {
+        self.set_extended_filter(slot, ExtendedFilter::disable());
+    }
+
+    /// Read back the Extended Address CAN filter in slot 'id'
+    #[inline]
+    pub fn get_extended_filter(&self, slot: ExtendedFilterSlot) -> ExtendedFilter {
+        ActivateFilter::read(&self.msg_ram().filters.flesa[slot as usize])
+    }
+
     /// Set an array of Extended Address CAN filters and overwrite the current set
     pub fn set_extended_filters(
         &mut self,
@@ -424,15 +1199,19 @@ where
 
     /// Retrieve the current protocol status
     pub fn get_protocol_status(&self) -> ProtocolStatus {
-        let psr = self.registers().psr.read();
-        ProtocolStatus {
-            activity: Activity::try_from(0 /*psr.act().bits()*/).unwrap(), //TODO: stm32g4 does not allow reading from this register
-            transmitter_delay_comp: psr.tdcv().bits(),
-            bus_off_status: psr.bo().bit_is_set(),
-            error_warning: psr.ew().bit_is_set(),
-            error_passive_state: psr.ep().bit_is_set(),
-            last_error: LastErrorCode::try_from(psr.lec().bits()).unwrap(),
-        }
+        ProtocolStatus::from_psr(&self.registers().psr.read())
+    }
+
+    /// Returns a snapshot of the transmit, receive and error state, see [`FdCanControl::status`]
+    #[inline]
+    pub fn status(&self) -> CanStatus {
+        self.control.status()
+    }
+
+    /// Returns the set of interrupt flags which are currently pending
+    #[inline]
+    pub fn interrupts_pending(&self) -> Interrupts {
+        self.control.interrupts_pending()
     }
 
     /// Check if the interrupt is triggered
@@ -487,6 +1266,12 @@ where
     }
 
     /// Combines an FdCanControl, Tx and the two Rx instances back into an FdCan instance
+    ///
+    /// Only the control half carries state, the other halves are consumed to prove that no
+    /// handle to the registers is left behind. They can not belong to another instance or mode:
+    /// all four are tied to `I` and `MODE` by their types, none of them can be cloned or change
+    /// mode on its own, and `split` hands out a single set per instance. The control half of
+    /// `split_rx` is a [`FdCanRxControl`] instead, which only `combine_rx` accepts.
     #[inline]
     #[allow(clippy::type_complexity)]
     pub fn combine(
@@ -497,11 +1282,13 @@ where
             Rx<I, MODE, Fifo1>,
         ),
     ) -> Self {
-        Self::create_can(t.0.config, t.0.instance)
+        Self { control: t.0 }
     }
 }
 
 /// Select an FDCAN Clock Source
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
 pub enum FdCanClockSource {
     /// Select HSE as the FDCAN clock source
     HSE = 0b00,
@@ -518,15 +1305,30 @@ where
 {
     /// Creates a CAN interface.
     ///
-    /// Sets the FDCAN clock to the P clock if no FDCAN clock has been configured
-    /// If one has been configured, it will leave it as is.
+    /// Enables the FDCAN clock and sets it to the P clock if no FDCAN clock has been configured
+    /// If one has been configured, it will leave it as is. [`CanExt::fdcan`] calls this with
+    /// the pins of the instance.
+    ///
+    /// [`CanExt::fdcan`]: crate::can::CanExt::fdcan
+    ///
+    /// # Panics
+    ///
+    /// Panics if the peripheral does not respond, see [`FdCan::try_new`].
     #[inline]
-    pub fn new<TX, RX>(can_instance: I, _tx: TX, _rx: RX, rcc: &Rcc) -> Self
-    where
-        TX: sealed::Tx<I>,
-        RX: sealed::Rx<I>,
-    {
-        I::enable(&rcc.rb);
+    pub fn new(can_instance: I, rcc: &Rcc) -> Self {
+        match Self::try_new(can_instance, rcc) {
+            Ok(can) => can,
+            Err((_, e)) => panic!("{}", e),
+        }
+    }
+
+    /// Creates a CAN interface, as [`FdCan::new`], but returns an error instead of panicking
+    /// when the peripheral does not respond.
+    ///
+    /// The endianness register is read as a sanity check; it reads a wrong value when the
+    /// peripheral is not clocked. On failure the instance is handed back with the error.
+    pub fn try_new(can_instance: I, rcc: &Rcc) -> Result<Self, (I, InitError)> {
+        FdCanRcc::enable(&rcc.rb);
 
         if rcc.rb.ccipr.read().fdcansel().is_hse() {
             // Select P clock as FDCAN clock source
@@ -543,8 +1345,10 @@ where
 
         let can = Self::create_can(FdCanConfig::default(), can_instance);
         let reg = can.registers();
-        assert!(reg.endn.read().bits() == 0x87654321_u32);
-        can
+        if reg.endn.read().bits() != 0x87654321_u32 {
+            return Err((can.control.instance, InitError::EndiannessCheckFailed));
+        }
+        Ok(can)
     }
 
     /// Creates a CAN interface.
@@ -553,17 +1357,11 @@ where
     /// Note that this is shared across all instances.
     /// Do not call this if there is allready an active FDCAN instance.
     #[inline]
-    pub fn new_with_clock_source<TX, RX>(
+    pub fn new_with_clock_source(
         can_instance: I,
-        _tx: TX,
-        _rx: RX,
         rcc: &Rcc,
         clock_source: FdCanClockSource,
-    ) -> Self
-    where
-        TX: sealed::Tx<I>,
-        RX: sealed::Rx<I>,
-    {
+    ) -> Self {
         rcc.rb.ccipr.modify(|_, w| {
             // This is sound, as `FdCanClockSource` only contains valid values for this field.
             unsafe {
@@ -573,7 +1371,14 @@ where
             w
         });
 
-        Self::new(can_instance, _tx, _rx, rcc)
+        Self::new(can_instance, rcc)
+    }
+
+    /// Creates a CAN interface without touching the RCC, for [`CanExt::fdcan_unchecked`]
+    ///
+    /// [`CanExt::fdcan_unchecked`]: crate::can::CanExt::fdcan_unchecked
+    pub(crate) fn new_unchecked(can_instance: I) -> Self {
+        Self::create_can(FdCanConfig::default(), can_instance)
     }
 
     /// Moves out of PoweredDownMode and into ConfigMode
@@ -582,6 +1387,30 @@ where
         self.set_power_down_mode(false);
         self.enter_init_mode();
 
+        self.init_config_mode()
+    }
+
+    /// As `into_config_mode`, but gives up when the peripheral does not leave power down mode,
+    /// or does not enter init mode, after polling it `spins` times.
+    ///
+    /// On a timeout the instance is handed back, so it can be reset with
+    /// [`reset_all`](crate::can::reset_all). The [`ModeError`] tells which of the two requests
+    /// was not acknowledged.
+    pub fn into_config_mode_timeout(
+        mut self,
+        spins: u32,
+    ) -> Result<FdCan<I, ConfigMode>, (Self, ModeError)> {
+        let woken = self
+            .set_power_down_mode_within(false, Some(spins))
+            .and_then(|_| self.enter_init_mode_within(Some(spins)));
+        match woken {
+            Ok(()) => Ok(self.init_config_mode()),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    /// Sets up the framework specific settings, with the peripheral in init mode
+    fn init_config_mode(mut self) -> FdCan<I, ConfigMode> {
         self.reset_msg_ram();
 
         let can = self.registers();
@@ -607,7 +1436,7 @@ where
                 .bits(STANDARD_FILTER_MAX)
         });
         for fid in 0..STANDARD_FILTER_MAX {
-            self.set_standard_filter((fid as u8).into(), StandardFilter::disable());
+            self.set_standard_filter(fid.into(), StandardFilter::disable());
         }
         for fid in 0..EXTENDED_FILTER_MAX {
             self.set_extended_filter(fid.into(), ExtendedFilter::disable());
@@ -620,6 +1449,7 @@ where
     #[inline]
     pub fn free(mut self) -> I {
         self.disable_interrupts(Interrupts::all());
+        CKDIV_USERS.fetch_and(!(1 << instance_index::<I>()), Ordering::Relaxed);
 
         //TODO check this!
         self.enter_init_mode();
@@ -634,12 +1464,48 @@ where
 {
     #[inline]
     fn leave_init_mode(&mut self) {
-        self.apply_config(self.control.config);
+        // Without a limit this can not time out
+        self.leave_init_mode_within(None).unwrap_or(());
+    }
+
+    #[inline]
+    fn leave_init_mode_within(&mut self, spins: Option<u32>) -> Result<(), ModeError> {
+        self.write_config(self.control.config);
 
         let can = self.registers();
         can.cccr.modify(|_, w| w.cce().clear_bit());
         can.cccr.modify(|_, w| w.init().clear_bit());
-        while can.cccr.read().init().bit_is_set() {}
+        spin_until(spins, || can.cccr.read().init().bit_is_clear())
+            .map_err(|Timeout| ModeError::InitTimeout)
+    }
+
+    /// Leaves init mode within `spins` polls and moves to `NEW_MODE`, or hands back `self`
+    fn leave_init_mode_timeout<NEW_MODE>(
+        mut self,
+        spins: u32,
+    ) -> Result<FdCan<I, NEW_MODE>, (Self, ModeError)> {
+        match self.leave_init_mode_within(Some(spins)) {
+            Ok(()) => Ok(self.into_can_mode()),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    /// Zeroes the message RAM section of this instance.
+    ///
+    /// This removes stale frames from the Rx FIFOs, Tx buffers and Tx event FIFO, and disables
+    /// all filters. It can be called any number of times before leaving ConfigMode.
+    ///
+    /// Note that the STM32G4 message RAM is not ECC protected, so it does not need to be
+    /// initialized before use; access problems are only reported through
+    /// [`Interrupt::MsgRamAccessFailure`].
+    pub fn clear_message_ram(&mut self) {
+        let words = core::mem::size_of::<message_ram::RegisterBlock>() / 4;
+        let ram = I::MSG_RAM as *mut u32;
+        for i in 0..words {
+            // Safety: The peripheral does not access the message RAM while in ConfigMode, and
+            // `i` stays within the section of this instance.
+            unsafe { core::ptr::write_volatile(ram.add(i), 0) };
+        }
     }
 
     /// Moves out of ConfigMode and into InternalLoopbackMode
@@ -651,6 +1517,19 @@ where
         self.into_can_mode()
     }
 
+    /// As `into_internal_loopback`, but gives up when the peripheral does not leave init mode after
+    /// polling it `spins` times.
+    ///
+    /// On a timeout the instance is handed back, so it can be reset with
+    /// [`reset_all`](crate::can::reset_all).
+    pub fn into_internal_loopback_timeout(
+        mut self,
+        spins: u32,
+    ) -> Result<FdCan<I, InternalLoopbackMode>, (Self, ModeError)> {
+        self.set_loopback_mode(LoopbackMode::Internal);
+        self.leave_init_mode_timeout(spins)
+    }
+
     /// Moves out of ConfigMode and into ExternalLoopbackMode
     #[inline]
     pub fn into_external_loopback(mut self) -> FdCan<I, ExternalLoopbackMode> {
@@ -660,6 +1539,19 @@ where
         self.into_can_mode()
     }
 
+    /// As `into_external_loopback`, but gives up when the peripheral does not leave init mode after
+    /// polling it `spins` times.
+    ///
+    /// On a timeout the instance is handed back, so it can be reset with
+    /// [`reset_all`](crate::can::reset_all).
+    pub fn into_external_loopback_timeout(
+        mut self,
+        spins: u32,
+    ) -> Result<FdCan<I, ExternalLoopbackMode>, (Self, ModeError)> {
+        self.set_loopback_mode(LoopbackMode::External);
+        self.leave_init_mode_timeout(spins)
+    }
+
     /// Moves out of ConfigMode and into RestrictedOperationMode
     #[inline]
     pub fn into_restricted(mut self) -> FdCan<I, RestrictedOperationMode> {
@@ -669,6 +1561,19 @@ where
         self.into_can_mode()
     }
 
+    /// As `into_restricted`, but gives up when the peripheral does not leave init mode after
+    /// polling it `spins` times.
+    ///
+    /// On a timeout the instance is handed back, so it can be reset with
+    /// [`reset_all`](crate::can::reset_all).
+    pub fn into_restricted_timeout(
+        mut self,
+        spins: u32,
+    ) -> Result<FdCan<I, RestrictedOperationMode>, (Self, ModeError)> {
+        self.set_restricted_operations(true);
+        self.leave_init_mode_timeout(spins)
+    }
+
     /// Moves out of ConfigMode and into NormalOperationMode
     #[inline]
     pub fn into_normal(mut self) -> FdCan<I, NormalOperationMode> {
@@ -678,6 +1583,19 @@ where
         self.into_can_mode()
     }
 
+    /// As `into_normal`, but gives up when the peripheral does not leave init mode after
+    /// polling it `spins` times.
+    ///
+    /// On a timeout the instance is handed back, so it can be reset with
+    /// [`reset_all`](crate::can::reset_all).
+    pub fn into_normal_timeout(
+        mut self,
+        spins: u32,
+    ) -> Result<FdCan<I, NormalOperationMode>, (Self, ModeError)> {
+        self.set_normal_operations(true);
+        self.leave_init_mode_timeout(spins)
+    }
+
     /// Moves out of ConfigMode and into BusMonitoringMode
     #[inline]
     pub fn into_bus_monitoring(mut self) -> FdCan<I, BusMonitoringMode> {
@@ -687,6 +1605,19 @@ where
         self.into_can_mode()
     }
 
+    /// As `into_bus_monitoring`, but gives up when the peripheral does not leave init mode after
+    /// polling it `spins` times.
+    ///
+    /// On a timeout the instance is handed back, so it can be reset with
+    /// [`reset_all`](crate::can::reset_all).
+    pub fn into_bus_monitoring_timeout(
+        mut self,
+        spins: u32,
+    ) -> Result<FdCan<I, BusMonitoringMode>, (Self, ModeError)> {
+        self.set_bus_monitoring_mode(true);
+        self.leave_init_mode_timeout(spins)
+    }
+
     /// Moves out of ConfigMode and into Testmode
     #[inline]
     pub fn into_test_mode(mut self) -> FdCan<I, TestMode> {
@@ -696,6 +1627,19 @@ where
         self.into_can_mode()
     }
 
+    /// As `into_test_mode`, but gives up when the peripheral does not leave init mode after
+    /// polling it `spins` times.
+    ///
+    /// On a timeout the instance is handed back, so it can be reset with
+    /// [`reset_all`](crate::can::reset_all).
+    pub fn into_test_mode_timeout(
+        mut self,
+        spins: u32,
+    ) -> Result<FdCan<I, TestMode>, (Self, ModeError)> {
+        self.set_test_mode(true);
+        self.leave_init_mode_timeout(spins)
+    }
+
     /// Moves out of ConfigMode and into PoweredDownmode
     #[inline]
     pub fn into_powered_down(mut self) -> FdCan<I, PoweredDownMode> {
@@ -705,10 +1649,54 @@ where
         self.into_can_mode()
     }
 
+    /// As `into_powered_down`, but gives up when the peripheral does not acknowledge the
+    /// power down request, or does not leave init mode, after polling it `spins` times.
+    ///
+    /// On a timeout the instance is handed back, so it can be reset with
+    /// [`reset_all`](crate::can::reset_all). The [`ModeError`] tells which of the two requests
+    /// was not acknowledged.
+    pub fn into_powered_down_timeout(
+        mut self,
+        spins: u32,
+    ) -> Result<FdCan<I, PoweredDownMode>, (Self, ModeError)> {
+        match self.set_power_down_mode_within(true, Some(spins)) {
+            Ok(()) => self.leave_init_mode_timeout(spins),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    /// Stores and applies a complete FdCanConfig at once
+    ///
+    /// This allows a config to be built with the `[FdCanConfig]` setters and reused for multiple
+    /// instances. The stored config is applied again when leaving ConfigMode.
+    ///
+    /// Fails as [`Self::apply_config`], handing back `self` unchanged.
+    #[inline]
+    pub fn with_config(mut self, config: FdCanConfig) -> Result<Self, (Self, ConfigError)> {
+        match self.apply_config(config) {
+            Ok(()) => {
+                self.control.config = config;
+                Ok(self)
+            }
+            Err(e) => Err((self, e)),
+        }
+    }
+
     /// Applies the settings of a new FdCanConfig
     /// See `[FdCanConfig]` for more information
+    ///
+    /// Returns [`ConfigError::ClockDividerInUse`] without changing anything when the clock
+    /// divider of `config` differs from the one of another instance, see
+    /// [`Self::set_clock_divider`].
     #[inline]
-    pub fn apply_config(&mut self, config: FdCanConfig) {
+    pub fn apply_config(&mut self, config: FdCanConfig) -> Result<(), ConfigError> {
+        self.check_clock_divider(config.clock_divider)?;
+        self.write_config(config);
+        Ok(())
+    }
+
+    /// Writes all settings of `config`, the clock divider only if no other instance uses one
+    fn write_config(&mut self, config: FdCanConfig) {
         self.set_data_bit_timing(config.dbtr);
         self.set_nominal_bit_timing(config.nbtr);
         self.set_automatic_retransmit(config.automatic_retransmit);
@@ -718,7 +1706,12 @@ where
         self.set_non_iso_mode(config.non_iso_mode);
         self.set_edge_filtering(config.edge_filtering);
         self.set_protocol_exception_handling(config.protocol_exception_handling);
+        self.set_auto_restricted_on_error(config.auto_restricted_on_error);
         self.set_global_filter(config.global_filter);
+        self.set_rx_fifo0_mode(config.rx_fifo0_mode);
+        self.set_rx_fifo1_mode(config.rx_fifo1_mode);
+        self.claim_clock_divider(config.clock_divider);
+        self.set_timestamp_counter_source(config.timestamp_source);
     }
 
     /// Configures the bit timings.
@@ -733,8 +1726,12 @@ where
     ///
     /// Then copy the `CAN_BUS_TIME` register value from the table and pass it as the `btr`
     /// parameter to this method.
+    ///
+    /// In debug builds this panics if `btr` is out of range, see
+    /// [`NominalBitTiming::validate`] and [`Self::try_set_nominal_bit_timing`].
     #[inline]
     pub fn set_nominal_bit_timing(&mut self, btr: NominalBitTiming) {
+        debug_assert_eq!(btr.validate(), Ok(()), "Invalid nominal bit timing");
         self.control.config.nbtr = btr;
 
         let can = self.registers();
@@ -742,7 +1739,7 @@ where
             w.nbrp()
                 .bits(btr.nbrp() - 1)
                 .ntseg1()
-                .bits(btr.ntseg1() - 1)
+                .bits((btr.ntseg1() - 1) as u8)
                 .ntseg2()
                 .bits(btr.ntseg2() - 1)
                 .nsjw()
@@ -750,10 +1747,46 @@ where
         });
     }
 
+    /// Configures the nominal bit timings, after checking them against the hardware limits.
+    #[inline]
+    pub fn try_set_nominal_bit_timing(
+        &mut self,
+        btr: NominalBitTiming,
+    ) -> Result<(), BitTimingError> {
+        btr.validate()?;
+        self.set_nominal_bit_timing(btr);
+        Ok(())
+    }
+
+    /// Writes `nbtp` to the Nominal Bit Timing and Prescaler register (NBTP) as is.
+    ///
+    /// This is an escape hatch for precomputed register values, such as those from the
+    /// `can_bit_timings` crate. Each field holds its value minus one:
+    ///
+    /// | Bits  | Field  | Value                    |
+    /// |-------|--------|--------------------------|
+    /// | 31:25 | NSJW   | sync jump width - 1      |
+    /// | 24:16 | NBRP   | prescaler - 1            |
+    /// | 15:8  | NTSEG1 | segment 1 - 1            |
+    /// | 6:0   | NTSEG2 | segment 2 - 1            |
+    ///
+    /// The decoded timing is kept in the stored configuration.
+    #[inline]
+    pub fn set_nominal_bit_timing_raw(&mut self, nbtp: u32) {
+        self.control.config.nbtr = NominalBitTiming::from_nbtp(nbtp);
+
+        let can = self.registers();
+        can.nbtp.write(|w| unsafe { w.bits(nbtp) });
+    }
+
     /// Configures the data bit timings for the FdCan Variable Bitrates.
     /// This is not used when frame_transmit is set to anything other than AllowFdCanAndBRS.
+    ///
+    /// In debug builds this panics if `btr` is out of range, see [`DataBitTiming::validate`]
+    /// and [`Self::try_set_data_bit_timing`].
     #[inline]
     pub fn set_data_bit_timing(&mut self, btr: DataBitTiming) {
+        debug_assert_eq!(btr.validate(), Ok(()), "Invalid data bit timing");
         self.control.config.dbtr = btr;
 
         let can = self.registers();
@@ -769,12 +1802,42 @@ where
         });
     }
 
+    /// Configures the data bit timings, after checking them against the hardware limits.
+    #[inline]
+    pub fn try_set_data_bit_timing(&mut self, btr: DataBitTiming) -> Result<(), BitTimingError> {
+        btr.validate()?;
+        self.set_data_bit_timing(btr);
+        Ok(())
+    }
+
+    /// Writes `dbtp` to the Data Bit Timing and Prescaler register (DBTP) as is.
+    ///
+    /// As [`Self::set_nominal_bit_timing_raw`], with the layout:
+    ///
+    /// | Bits  | Field  | Value                           |
+    /// |-------|--------|---------------------------------|
+    /// | 23    | TDC    | transceiver delay compensation  |
+    /// | 20:16 | DBRP   | prescaler - 1                   |
+    /// | 12:8  | DTSEG1 | segment 1 - 1                   |
+    /// | 7:4   | DTSEG2 | segment 2 - 1                   |
+    /// | 3:0   | DSJW   | sync jump width - 1             |
+    #[inline]
+    pub fn set_data_bit_timing_raw(&mut self, dbtp: u32) {
+        self.control.config.dbtr = DataBitTiming::from_dbtp(dbtp);
+
+        let can = self.registers();
+        can.dbtp.write(|w| unsafe { w.bits(dbtp) });
+    }
+
     /// Enables or disables automatic retransmission of messages
     ///
     /// If this is enabled, the CAN peripheral will automatically try to retransmit each frame
     /// util it can be sent. Otherwise, it will try only once to send each frame.
     ///
     /// Automatic retransmission is enabled by default.
+    ///
+    /// The DAR bit is protected and can only be written in ConfigMode. To change it in one of
+    /// the receiving modes, see [`FdCan::with_retransmit_changed`].
     #[inline]
     pub fn set_automatic_retransmit(&mut self, enabled: bool) {
         let can = self.registers();
@@ -787,7 +1850,7 @@ where
     #[inline]
     pub fn set_transmit_pause(&mut self, enabled: bool) {
         let can = self.registers();
-        can.cccr.modify(|_, w| w.dar().bit(!enabled));
+        can.cccr.modify(|_, w| w.txp().bit(enabled));
         self.control.config.transmit_pause = enabled;
     }
 
@@ -802,6 +1865,8 @@ where
 
     /// Configures edge filtering
     /// See `[FdCanConfig]` for more information
+    ///
+    /// Edge filtering is disabled after reset, which matches the default configuration.
     #[inline]
     pub fn set_edge_filtering(&mut self, enabled: bool) {
         let can = self.registers();
@@ -830,8 +1895,9 @@ where
     #[inline]
     pub fn set_interrupt_line_config(&mut self, l0int: Interrupts) {
         let can = self.registers();
+        let ils = InterruptLineConfig::from(l0int);
 
-        can.ils.modify(|_, w| unsafe { w.bits(l0int.bits()) });
+        can.ils.modify(|_, w| unsafe { w.bits(ils.to_bits()) });
 
         self.control.config.interrupt_line_config = l0int;
     }
@@ -846,25 +1912,65 @@ where
         self.control.config.protocol_exception_handling = enabled;
     }
 
-    /// Sets the General FdCAN clock divider for this instance
-    //TODO: ?clock divider is a shared register?
+    /// Allows entering restricted operation on protocol errors, see
+    /// [`FdCanConfig::auto_restricted_on_error`]
     #[inline]
-    pub fn set_clock_divider(&mut self, div: ClockDivider) {
-        let can = self.registers();
+    pub fn set_auto_restricted_on_error(&mut self, enabled: bool) {
+        self.control.config.auto_restricted_on_error = enabled;
+    }
+
+    /// Sets the General FdCAN clock divider
+    ///
+    /// **CKDIV is a single register shared by all FDCAN instances.** Changing it here also
+    /// changes the kernel clock, and so the bit rate, of every other instance, including ones
+    /// which are already running. Configure every instance with the same divider.
+    ///
+    /// To keep the running instances intact, this returns [`ConfigError::ClockDividerInUse`]
+    /// without writing the divider when it differs from the one configured by another instance
+    /// which has not been freed since. [`Self::apply_config`] does the same.
+    ///
+    /// When leaving ConfigMode the stored config is applied again; if another instance
+    /// configured a different divider in the meantime, that divider is left in place.
+    #[inline]
+    pub fn set_clock_divider(&mut self, div: ClockDivider) -> Result<(), ConfigError> {
+        self.check_clock_divider(div)?;
+        self.write_clock_divider(div);
+        Ok(())
+    }
+
+    /// Returns an error if another instance uses a clock divider other than `div`
+    fn check_clock_divider(&self, div: ClockDivider) -> Result<(), ConfigError> {
+        let others = CKDIV_USERS.load(Ordering::Relaxed) & !(1 << instance_index::<I>());
+        if others != 0 && CKDIV_SHARED.load(Ordering::Relaxed) != div as u8 {
+            Err(ConfigError::ClockDividerInUse)
+        } else {
+            Ok(())
+        }
+    }
 
-        can.ckdiv.write(|w| unsafe { w.pdiv().bits(div as u8) });
+    fn write_clock_divider(&mut self, div: ClockDivider) {
+        CKDIV_USERS.fetch_or(1 << instance_index::<I>(), Ordering::Relaxed);
+        CKDIV_SHARED.store(div as u8, Ordering::Relaxed);
+
+        self.registers()
+            .ckdiv
+            .write(|w| unsafe { w.pdiv().bits(div as u8) });
 
         self.control.config.clock_divider = div;
     }
 
+    /// Sets the clock divider of a config, unless another instance uses a different one
+    fn claim_clock_divider(&mut self, div: ClockDivider) {
+        if self.check_clock_divider(div).is_ok() {
+            self.write_clock_divider(div);
+        }
+    }
+
     /// Configures and resets the timestamp counter
     #[inline]
     pub fn set_timestamp_counter_source(&mut self, select: TimestampSource) {
-        let (tcp, tss) = match select {
-            TimestampSource::None => (0, 0b00),
-            TimestampSource::Prescaler(p) => (p as u8, 0b01),
-            TimestampSource::FromTIM3 => (0, 0b10),
-        };
+        // See `use_tim3_timestamp` to configure TIM3 for `FromTIM3`
+        let (tss, tcp) = select.tscc();
         self.registers()
             .tscc
             .write(|w| unsafe { w.tcp().bits(tcp).tss().bits(tss) });
@@ -872,21 +1978,130 @@ where
         self.control.config.timestamp_source = select;
     }
 
+    /// Uses the counter of TIM3 as the source of the timestamp counter
+    ///
+    /// TIM3 is enabled and set up to count up freely at `f_TIM3 / (psc + 1)`, with `f_TIM3`
+    /// being the APB1 timer clock. Frames are timestamped with the TIM3 counter value, so the
+    /// resolution of the timestamp is `(psc + 1) / f_TIM3` and it wraps around every 65536
+    /// ticks. TIM3 must not be reconfigured while it is used as timestamp source.
+    #[inline]
+    pub fn use_tim3_timestamp(&mut self, tim3: &mut TIM3, psc: u16, rcc: &Rcc) {
+        TIM3::enable(&rcc.rb);
+
+        tim3.cr1.modify(|_, w| w.cen().clear_bit());
+        tim3.psc.write(|w| unsafe { w.psc().bits(psc) });
+        tim3.arr.write(|w| unsafe { w.bits(0xFFFF) });
+        // Load the new prescaler value
+        tim3.egr.write(|w| w.ug().set_bit());
+        tim3.cr1.modify(|_, w| w.cen().set_bit());
+
+        self.set_timestamp_counter_source(TimestampSource::FromTIM3);
+    }
+
     /// Configures the global filter settings
     #[inline]
     pub fn set_global_filter(&mut self, filter: GlobalFilter) {
-        self.registers().rxgfc.modify(|_, w| {
-            unsafe {
-                w.anfs()
-                    .bits(filter.handle_standard_frames as u8)
-                    .anfe()
-                    .bits(filter.handle_extended_frames as u8)
-            }
-            .rrfs()
-            .bit(filter.reject_remote_standard_frames)
-            .rrfe()
-            .bit(filter.reject_remote_extended_frames)
-        });
+        self.control.config.global_filter = filter;
+        self.write_rxgfc();
+    }
+
+    /// Installs `filters` in consecutive Standard filter slots, starting at slot 0, and disables
+    /// the remaining slots
+    ///
+    /// Returns the number of installed filters. If `filters` yields more than
+    /// [`STANDARD_FILTER_MAX`] filters, all slots are filled with the first ones and
+    /// `TooManyFilters` is returned.
+    pub fn install_standard_filters(
+        &mut self,
+        filters: impl IntoIterator<Item = StandardFilter>,
+    ) -> Result<usize, TooManyFilters> {
+        let mut filters = filters.into_iter();
+        let slots = &mut self.msg_ram_mut().filters.flssa;
+        let mut count = 0;
+        for (slot, filter) in slots.iter_mut().zip(filters.by_ref()) {
+            slot.activate(filter);
+            count += 1;
+        }
+        for slot in &mut slots[count..] {
+            slot.activate(StandardFilter::disable());
+        }
+        match filters.next() {
+            Some(_) => Err(TooManyFilters),
+            None => Ok(count),
+        }
+    }
+
+    /// Installs `filters` in consecutive Extended filter slots, starting at slot 0, and disables
+    /// the remaining slots
+    ///
+    /// Returns the number of installed filters. If `filters` yields more than
+    /// [`EXTENDED_FILTER_MAX`] filters, all slots are filled with the first ones and
+    /// `TooManyFilters` is returned.
+    pub fn install_extended_filters(
+        &mut self,
+        filters: impl IntoIterator<Item = ExtendedFilter>,
+    ) -> Result<usize, TooManyFilters> {
+        let mut filters = filters.into_iter();
+        let slots = &mut self.msg_ram_mut().filters.flesa;
+        let mut count = 0;
+        for (slot, filter) in slots.iter_mut().zip(filters.by_ref()) {
+            slot.activate(filter);
+            count += 1;
+        }
+        for slot in &mut slots[count..] {
+            slot.activate(ExtendedFilter::disable());
+        }
+        match filters.next() {
+            Some(_) => Err(TooManyFilters),
+            None => Ok(count),
+        }
+    }
+
+    /// Handles all frames according to `target`, standard and extended alike
+    ///
+    /// This overwrites standard filter slot 0 and extended filter slot 0 with accept-all
+    /// filters, and sets the global filter to handle non-matching frames the same way.
+    pub fn accept_all_frames(&mut self, target: FilterTarget) {
+        self.set_standard_filter(StandardFilterSlot::_0, StandardFilter::accept_all(target));
+        self.set_extended_filter(ExtendedFilterSlot::_0, ExtendedFilter::accept_all(target));
+        self.set_global_filter(
+            self.control
+                .config
+                .global_filter
+                .set_handle_standard_frames(target.into())
+                .set_handle_extended_frames(target.into()),
+        );
+    }
+
+    /// Sets up a number of filters without keeping track of their slots
+    ///
+    /// Each filter added to the [`FilterBank`] is placed in the first disabled slot, which is
+    /// returned so the filter can be changed or disabled later on.
+    pub fn configure_filters<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut FilterBank<'_, I>),
+    {
+        f(&mut FilterBank { can: self });
+    }
+
+    /// Configures the operating mode of Rx FIFO 0
+    /// See `[RxFifoMode]` for more information
+    #[inline]
+    pub fn set_rx_fifo0_mode(&mut self, mode: RxFifoMode) {
+        self.registers()
+            .rxgfc
+            .modify(|_, w| w.f0om().bit(mode == RxFifoMode::Overwrite));
+        self.control.config.rx_fifo0_mode = mode;
+    }
+
+    /// Configures the operating mode of Rx FIFO 1
+    /// See `[RxFifoMode]` for more information
+    #[inline]
+    pub fn set_rx_fifo1_mode(&mut self, mode: RxFifoMode) {
+        self.registers()
+            .rxgfc
+            .modify(|_, w| w.f1om().bit(mode == RxFifoMode::Overwrite));
+        self.control.config.rx_fifo1_mode = mode;
     }
 
     /// Returns the current FdCan timestamp counter
@@ -896,10 +2111,52 @@ where
     }
 }
 
+/// Places filters in the free filter slots, see [`FdCan::configure_filters`]
+pub struct FilterBank<'a, I: Instance> {
+    can: &'a mut FdCan<I, ConfigMode>,
+}
+impl<I> FilterBank<'_, I>
+where
+    I: Instance,
+{
+    /// Places `filter` in the first disabled standard filter slot and returns that slot
+    ///
+    /// If all standard filter slots are in use, `TooManyFilters` is returned.
+    pub fn add_standard(
+        &mut self,
+        filter: StandardFilter,
+    ) -> Result<StandardFilterSlot, TooManyFilters> {
+        let slot = (0..STANDARD_FILTER_MAX)
+            .map(StandardFilterSlot::from)
+            .find(|slot| self.can.get_standard_filter(*slot).action == Action::Disable)
+            .ok_or(TooManyFilters)?;
+        self.can.set_standard_filter(slot, filter);
+        Ok(slot)
+    }
+
+    /// Places `filter` in the first disabled extended filter slot and returns that slot
+    ///
+    /// If all extended filter slots are in use, `TooManyFilters` is returned.
+    pub fn add_extended(
+        &mut self,
+        filter: ExtendedFilter,
+    ) -> Result<ExtendedFilterSlot, TooManyFilters> {
+        let slot = (0..EXTENDED_FILTER_MAX)
+            .map(ExtendedFilterSlot::from)
+            .find(|slot| self.can.get_extended_filter(*slot).action == Action::Disable)
+            .ok_or(TooManyFilters)?;
+        self.can.set_extended_filter(slot, filter);
+        Ok(slot)
+    }
+}
+
 impl<I> FdCan<I, InternalLoopbackMode>
 where
     I: Instance,
 {
+    /// Number of polls after which [`FdCan::selftest`] gives up.
+    const SELFTEST_SPINS: u32 = 1_000_000;
+
     /// Returns out of InternalLoopbackMode and back into ConfigMode
     #[inline]
     pub fn into_config_mode(mut self) -> FdCan<I, ConfigMode> {
@@ -908,6 +2165,61 @@ where
 
         self.into_can_mode()
     }
+
+    /// As `into_config_mode`, but gives up when the peripheral does not enter init mode after
+    /// polling it `spins` times.
+    ///
+    /// On a timeout the instance is handed back, so it can be reset with
+    /// [`reset_all`](crate::can::reset_all).
+    pub fn into_config_mode_timeout(
+        mut self,
+        spins: u32,
+    ) -> Result<FdCan<I, ConfigMode>, (Self, ModeError)> {
+        self.set_loopback_mode(LoopbackMode::None);
+        self.enter_init_mode_timeout(spins)
+    }
+
+    /// Transmits a frame and verifies it is received back on FIFO_0, byte-for-byte.
+    ///
+    /// This confirms that the peripheral, its clock and the message RAM work, without needing
+    /// a transceiver or bus. Payloads longer than 8 bytes are sent as FdCan frames, which
+    /// requires a `FrameTransmissionConfig` that allows them, or [`SelfTestError::Frame`] is
+    /// returned. The filters must route `id` into FIFO_0, and FIFO_0 should be empty when
+    /// calling this.
+    pub fn selftest(&mut self, id: Id, payload: &[u8]) -> Result<(), SelfTestError> {
+        let len = u8::try_from(payload.len())
+            .ok()
+            .filter(|len| len_to_dlc(*len).is_some())
+            .ok_or(SelfTestError::InvalidLength)?;
+        let header = if len > 8 {
+            TxFrameHeader::new(id, len).fd()
+        } else {
+            TxFrameHeader::new(id, len)
+        };
+
+        let mut write = |words: &mut [u32]| pack_words(words, payload);
+        (0..Self::SELFTEST_SPINS)
+            .find_map(|_| match self.transmit(header, &mut write) {
+                Ok(_) => Some(Ok(())),
+                Err(nb::Error::WouldBlock) => None,
+                Err(nb::Error::Other(e)) => Some(Err(SelfTestError::Frame(e))),
+            })
+            .unwrap_or(Err(SelfTestError::TransmitTimeout))?;
+
+        let received = (0..Self::SELFTEST_SPINS)
+            .find_map(|_| {
+                self.receive0(&mut |header, data| ReceivedFrame::new(header, data))
+                    .ok()
+            })
+            .ok_or(SelfTestError::ReceiveTimeout)?
+            .unwrap();
+
+        if received.header.id == id && received.data() == payload {
+            Ok(())
+        } else {
+            Err(SelfTestError::Mismatch)
+        }
+    }
 }
 
 impl<I> FdCan<I, ExternalLoopbackMode>
@@ -922,6 +2234,19 @@ where
 
         self.into_can_mode()
     }
+
+    /// As `into_config_mode`, but gives up when the peripheral does not enter init mode after
+    /// polling it `spins` times.
+    ///
+    /// On a timeout the instance is handed back, so it can be reset with
+    /// [`reset_all`](crate::can::reset_all).
+    pub fn into_config_mode_timeout(
+        mut self,
+        spins: u32,
+    ) -> Result<FdCan<I, ConfigMode>, (Self, ModeError)> {
+        self.set_loopback_mode(LoopbackMode::None);
+        self.enter_init_mode_timeout(spins)
+    }
 }
 
 impl<I> FdCan<I, NormalOperationMode>
@@ -929,6 +2254,9 @@ where
     I: Instance,
 {
     /// Returns out of NormalOperationMode and back into ConfigMode
+    ///
+    /// The filters and the stored configuration are preserved, so only the settings which are
+    /// changed in ConfigMode need to be set again before returning to NormalOperationMode.
     #[inline]
     pub fn into_config_mode(mut self) -> FdCan<I, ConfigMode> {
         self.set_normal_operations(false);
@@ -936,6 +2264,46 @@ where
 
         self.into_can_mode()
     }
+
+    /// As `into_config_mode`, but gives up when the peripheral does not enter init mode after
+    /// polling it `spins` times.
+    ///
+    /// On a timeout the instance is handed back, so it can be reset with
+    /// [`reset_all`](crate::can::reset_all).
+    pub fn into_config_mode_timeout(
+        mut self,
+        spins: u32,
+    ) -> Result<FdCan<I, ConfigMode>, (Self, ModeError)> {
+        self.set_normal_operations(false);
+        self.enter_init_mode_timeout(spins)
+    }
+
+    /// Returns `true` if the hardware has switched the node into restricted operation
+    ///
+    /// This happens after a Tx handler message RAM access failure, the node then no longer
+    /// transmits until it is taken back through `into_config_mode` and `into_normal`.
+    #[inline]
+    pub fn is_restricted(&self) -> bool {
+        self.registers().cccr.read().asm().bit_is_set()
+    }
+
+    /// Moves into RestrictedOperationMode when a protocol error or protocol exception occurred
+    ///
+    /// This only acts when [`FdCanConfig::auto_restricted_on_error`] is set, and otherwise
+    /// hands back the instance unchanged. The protocol error interrupt flags are left set.
+    /// Note that this reads the protocol status, which resets the last error code.
+    pub fn restrict_on_error(self) -> Result<FdCan<I, RestrictedOperationMode>, Self> {
+        if !self.control.config.auto_restricted_on_error {
+            return Err(self);
+        }
+        let errors = Interrupts::PROT_ERR_ARBRITATION | Interrupts::PROT_ERR_DATA;
+        let can = self.registers();
+        if !self.interrupts_pending().intersects(errors) && !can.psr.read().pxe().bit_is_set() {
+            return Err(self);
+        }
+
+        Ok(self.into_config_mode().into_restricted())
+    }
 }
 
 impl<I> FdCan<I, RestrictedOperationMode>
@@ -950,6 +2318,19 @@ where
 
         self.into_can_mode()
     }
+
+    /// As `into_config_mode`, but gives up when the peripheral does not enter init mode after
+    /// polling it `spins` times.
+    ///
+    /// On a timeout the instance is handed back, so it can be reset with
+    /// [`reset_all`](crate::can::reset_all).
+    pub fn into_config_mode_timeout(
+        mut self,
+        spins: u32,
+    ) -> Result<FdCan<I, ConfigMode>, (Self, ModeError)> {
+        self.set_restricted_operations(false);
+        self.enter_init_mode_timeout(spins)
+    }
 }
 
 impl<I> FdCan<I, BusMonitoringMode>
@@ -964,9 +2345,24 @@ where
 
         self.into_can_mode()
     }
+
+    /// As `into_config_mode`, but gives up when the peripheral does not enter init mode after
+    /// polling it `spins` times.
+    ///
+    /// On a timeout the instance is handed back, so it can be reset with
+    /// [`reset_all`](crate::can::reset_all).
+    pub fn into_config_mode_timeout(
+        mut self,
+        spins: u32,
+    ) -> Result<FdCan<I, ConfigMode>, (Self, ModeError)> {
+        self.set_bus_monitoring_mode(false);
+        self.enter_init_mode_timeout(spins)
+    }
 }
 
 /// states of the test.tx register
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
 pub enum TestTransmitPinState {
     /// CAN core has control (default)
     CoreHasControl = 0b00,
@@ -977,6 +2373,16 @@ pub enum TestTransmitPinState {
     /// Set to Recessive (1) Level
     SetRecessive = 0b11,
 }
+impl From<u8> for TestTransmitPinState {
+    fn from(value: u8) -> Self {
+        match value & 0b11 {
+            0b00 => Self::CoreHasControl,
+            0b01 => Self::ShowSamplePoint,
+            0b10 => Self::SetDominant,
+            _ => Self::SetRecessive,
+        }
+    }
+}
 
 impl<I> FdCan<I, TestMode>
 where
@@ -991,8 +2397,25 @@ where
         self.into_can_mode()
     }
 
+    /// As `into_config_mode`, but gives up when the peripheral does not enter init mode after
+    /// polling it `spins` times.
+    ///
+    /// On a timeout the instance is handed back, so it can be reset with
+    /// [`reset_all`](crate::can::reset_all).
+    pub fn into_config_mode_timeout(
+        mut self,
+        spins: u32,
+    ) -> Result<FdCan<I, ConfigMode>, (Self, ModeError)> {
+        self.set_test_mode(false);
+        self.enter_init_mode_timeout(spins)
+    }
+
     /// Gets the state of the receive pin to either Dominant (false), or Recessive (true)
-    pub fn get_receive_pin(&mut self) -> bool {
+    ///
+    /// Together with [`Self::set_transmit_pin`] this allows testing the connection to the
+    /// transceiver: with the transceiver enabled, a dominant or recessive transmit pin should be
+    /// read back on the receive pin, as long as no other node drives the bus dominant.
+    pub fn get_receive_pin(&self) -> bool {
         let can = self.registers();
 
         can.test.read().rx().bit_is_set()
@@ -1005,6 +2428,11 @@ where
         //SAFE: state has all possible values, and this can only occur in TestMode
         can.test.modify(|_, w| unsafe { w.tx().bits(state as u8) });
     }
+
+    /// Returns how the transmit pin is currently controlled, see [`Self::set_transmit_pin`]
+    pub fn get_transmit_pin(&self) -> TestTransmitPinState {
+        self.registers().test.read().tx().bits().into()
+    }
 }
 
 impl<I, M> FdCan<I, M>
@@ -1012,6 +2440,30 @@ where
     I: Instance,
     M: Transmit + Receive,
 {
+    /// Blocks until a frame has been received in either FIFO and returns it.
+    ///
+    /// FIFO_0 is checked first. Lost frames are not reported, use `receive0`/`receive1` when
+    /// that matters.
+    pub fn read_blocking(&mut self) -> ReceivedFrame {
+        loop {
+            if let Ok(frame) = self.receive0(&mut |h, data| ReceivedFrame::new(h, data)) {
+                return frame.unwrap();
+            }
+            if let Ok(frame) = self.receive1(&mut |h, data| ReceivedFrame::new(h, data)) {
+                return frame.unwrap();
+            }
+        }
+    }
+
+    /// Blocks until the frame with `header` and payload `data` has been put in a transmit
+    /// mailbox.
+    ///
+    /// `data` is padded with zeroes up to `header.len`. As with `transmit`, a pending frame with
+    /// a lower priority may be discarded to make room, and an invalid header is rejected.
+    pub fn write_blocking(&mut self, header: TxFrameHeader, data: &[u8]) -> Result<(), FrameError> {
+        nb::block!(self.transmit(header, &mut |words| pack_words(words, data))).map(|_| ())
+    }
+
     /// Splits this `FdCan` instance into transmitting and receiving halves, by reference.
     #[inline]
     #[allow(clippy::type_complexity)]
@@ -1027,6 +2479,15 @@ where
     }
 
     /// Consumes this `FdCan` instance and splits it into transmitting and receiving halves.
+    ///
+    /// The halves are owned and not tied to a borrow of `self`, and they are `Send`. They can
+    /// be moved into RTIC resources or a `Mutex<RefCell<_>>`, for example to receive from
+    /// within an interrupt while transmitting from the main thread. Each half only accesses
+    /// its own registers and message RAM section, and shared interrupt flags are cleared with
+    /// single writes, so they can be used concurrently. The exception is the interrupt enable
+    /// register, which `Rx::receive_async` and `FdCanControl` both modify, so these must not
+    /// preempt each other.
+    /// Use `combine` to get the `FdCan` instance back.
     #[allow(clippy::type_complexity)]
     pub fn split(
         self,
@@ -1056,7 +2517,7 @@ where
         &mut self,
         frame: TxFrameHeader,
         write: &mut WTX,
-    ) -> nb::Result<Option<()>, Infallible>
+    ) -> nb::Result<Option<()>, FrameError>
     where
         WTX: FnMut(&mut [u32]),
     {
@@ -1075,7 +2536,7 @@ where
         frame: TxFrameHeader,
         write: &mut WTX,
         pending: &mut PTX,
-    ) -> nb::Result<Option<P>, Infallible>
+    ) -> nb::Result<Option<P>, FrameError>
     where
         PTX: FnMut(Mailbox, TxFrameHeader, &[u32]) -> P,
         WTX: FnMut(&mut [u32]),
@@ -1084,6 +2545,93 @@ where
         unsafe { Tx::<I, M>::conjure().transmit_preserve(frame, write, pending) }
     }
 
+    /// Puts a CAN frame in a free transmit mailbox and returns the mailbox used.
+    ///
+    /// See [`Tx::transmit_indexed`].
+    #[inline]
+    pub fn transmit_indexed<WTX>(
+        &mut self,
+        frame: TxFrameHeader,
+        write: &mut WTX,
+    ) -> nb::Result<TransmitResult, FrameError>
+    where
+        WTX: FnMut(&mut [u32]),
+    {
+        // Safety: We have a `&mut self` and have unique access to the peripheral.
+        unsafe { Tx::<I, M>::conjure().transmit_indexed(frame, write) }
+    }
+
+    /// Puts a CAN frame in a free transmit mailbox for a single transmission attempt.
+    ///
+    /// See [`Tx::transmit_single_shot`].
+    #[inline]
+    pub fn transmit_single_shot<WTX>(
+        &mut self,
+        frame: TxFrameHeader,
+        write: &mut WTX,
+    ) -> nb::Result<Mailbox, FrameError>
+    where
+        WTX: FnMut(&mut [u32]),
+    {
+        // Safety: We have a `&mut self` and have unique access to the peripheral.
+        unsafe { Tx::<I, M>::conjure().transmit_single_shot(frame, write) }
+    }
+
+    /// Ends the transmission attempt of a frame put in `mailbox` by `transmit_single_shot`.
+    ///
+    /// See [`Tx::finish_single_shot`].
+    #[inline]
+    pub fn finish_single_shot(&mut self, mailbox: Mailbox) -> bool {
+        // Safety: We have a `&mut self` and have unique access to the peripheral.
+        unsafe { Tx::<I, M>::conjure().finish_single_shot(mailbox) }
+    }
+
+    /// Puts a CAN frame in a transmit mailbox, without the `nb` encoding of `transmit`.
+    ///
+    /// See [`Tx::try_transmit`].
+    #[inline]
+    pub fn try_transmit<WTX>(
+        &mut self,
+        frame: TxFrameHeader,
+        write: &mut WTX,
+    ) -> Result<TransmitOutcome, TransmitError>
+    where
+        WTX: FnMut(&mut [u32]),
+    {
+        // Safety: We have a `&mut self` and have unique access to the peripheral.
+        unsafe { Tx::<I, M>::conjure().try_transmit(frame, write) }
+    }
+
+    /// Puts a batch of CAN frames in free transmit mailboxes, returning how many were queued.
+    ///
+    /// See [`Tx::transmit_all`].
+    #[inline]
+    pub fn transmit_all(&mut self, frames: &[(TxFrameHeader, &[u8])]) -> Result<usize, BatchError> {
+        // Safety: We have a `&mut self` and have unique access to the peripheral.
+        unsafe { Tx::<I, M>::conjure().transmit_all(frames) }
+    }
+
+    /// Puts a CAN frame in a transmit mailbox and blocks until it has left the transmit queue.
+    ///
+    /// This blocks while the queue only holds frames with a higher priority. As with `transmit`,
+    /// a pending frame with a lower priority is discarded to make room. Returns as well when the
+    /// frame has been aborted, see [`Tx::wait_for_completion`]. An invalid header is rejected
+    /// without blocking.
+    pub fn transmit_and_wait<WTX>(
+        &mut self,
+        frame: TxFrameHeader,
+        write: &mut WTX,
+    ) -> Result<(), FrameError>
+    where
+        WTX: FnMut(&mut [u32]),
+    {
+        // Safety: We have a `&mut self` and have unique access to the peripheral.
+        let tx = unsafe { Tx::<I, M>::conjure_by_ref() };
+        let (mailbox, _) = nb::block!(tx.enqueue(frame, write, &mut |_, _, _| ()))?;
+        nb::block!(tx.wait_for_completion(mailbox)).unwrap();
+        Ok(())
+    }
+
     /// Returns `true` if no frame is pending for transmission.
     #[inline]
     pub fn is_transmitter_idle(&self) -> bool {
@@ -1103,6 +2651,14 @@ where
         // Safety: We have a `&mut self` and have unique access to the peripheral.
         unsafe { Tx::<I, M>::conjure().abort(mailbox) }
     }
+
+    /// As `abort`, but gives up when the abort request has not finished after polling it
+    /// `spins` times.
+    #[inline]
+    pub fn abort_timeout(&mut self, mailbox: Mailbox, spins: u32) -> Result<bool, Timeout> {
+        // Safety: We have a `&mut self` and have unique access to the peripheral.
+        unsafe { Tx::<I, M>::conjure().abort_within(mailbox, Some(spins)) }
+    }
 }
 
 impl<I, M> FdCan<I, M>
@@ -1110,76 +2666,395 @@ where
     I: Instance,
     M: Receive,
 {
+    /// Splits the receiving halves off this `FdCan` instance, by reference.
+    ///
+    /// Unlike `split_by_ref`, this is also available in modes which can not transmit, like
+    /// `BusMonitoringMode` and `RestrictedOperationMode`.
+    #[inline]
+    pub fn split_rx_by_ref(&mut self) -> (&mut Rx<I, M, Fifo0>, &mut Rx<I, M, Fifo1>) {
+        let (_, _, rx0, rx1) = self.split_by_ref_generic();
+        (rx0, rx1)
+    }
+
+    /// Consumes this `FdCan` instance and splits it into its control and receiving halves.
+    ///
+    /// Unlike `split`, this is also available in modes which can not transmit, like
+    /// `BusMonitoringMode` and `RestrictedOperationMode`. Use `combine_rx` to get the `FdCan`
+    /// instance back.
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn split_rx(self) -> (FdCanRxControl<I, M>, Rx<I, M, Fifo0>, Rx<I, M, Fifo1>) {
+        let (control, _, rx0, rx1) = self.split_generic();
+        (FdCanRxControl(control), rx0, rx1)
+    }
+
+    /// Combines an FdCanRxControl and the two Rx instances back into an FdCan instance
+    ///
+    /// Only the halves of `split_rx` are accepted. The control half of `split` can not be
+    /// combined here, as its `Tx` half would still be alive; use `combine` for these.
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn combine_rx(t: (FdCanRxControl<I, M>, Rx<I, M, Fifo0>, Rx<I, M, Fifo1>)) -> Self {
+        Self { control: t.0 .0 }
+    }
+
+    /// Stops or resumes accepting frames, without leaving the current mode
+    ///
+    /// While receiving is disabled, the filter lists are switched off and the global filter
+    /// rejects all frames, remote frames included. The filter slots and the configured global
+    /// filter are left untouched, and are in effect again once receiving is enabled. Changing
+    /// the global filter in `ConfigMode` meanwhile takes effect on enabling as well.
+    ///
+    /// The global filter can only be written in init mode, so this briefly enters it. The
+    /// peripheral stops communicating while in init mode, and the Tx queue and Rx FIFO status
+    /// are reset: pending frames are not transmitted and frames left in the Rx FIFOs are lost.
+    /// Drain the FIFOs and wait for the transmit queue to become idle first when that matters.
+    pub fn set_receive_enabled(&mut self, enabled: bool) {
+        if enabled != self.control.receive_paused {
+            return;
+        }
+        self.control.receive_paused = !enabled;
+        self.reinit_in_place(|can| can.write_rxgfc());
+    }
+
+    /// Enables or disables automatic retransmission, without leaving the current mode
+    ///
+    /// See [`FdCan::set_automatic_retransmit`]. The DAR bit can only be written in init mode, so
+    /// this briefly enters it. The peripheral stops communicating while in init mode, and the
+    /// Tx queue and Rx FIFO status are reset: pending frames are not transmitted and frames left
+    /// in the Rx FIFOs are lost. Drain the FIFOs and wait for the transmit queue to become idle
+    /// first when that matters.
+    pub fn with_retransmit_changed(mut self, enabled: bool) -> Self {
+        self.reinit_in_place(|can| {
+            can.registers().cccr.modify(|_, w| w.dar().bit(!enabled));
+            can.control.config.automatic_retransmit = enabled;
+        });
+        self
+    }
+
+    /// Returns `false` while receiving is disabled by `set_receive_enabled`
+    #[inline]
+    pub fn is_receive_enabled(&self) -> bool {
+        !self.control.receive_paused
+    }
+
     /// Returns a received frame from FIFO_0 if available.
     #[inline]
-    pub fn receive0<RECV, R>(
-        &mut self,
-        receive: &mut RECV,
-    ) -> nb::Result<ReceiveOverrun<R>, Infallible>
-    where
-        RECV: FnMut(RxFrameInfo, &[u32]) -> R,
-    {
-        // Safety: We have a `&mut self` and have unique access to the peripheral.
-        unsafe { Rx::<I, M, Fifo0>::conjure().receive(receive) }
+    pub fn receive0<RECV, R>(
+        &mut self,
+        receive: &mut RECV,
+    ) -> nb::Result<ReceiveOverrun<R>, Infallible>
+    where
+        RECV: FnMut(RxFrameInfo, &[u32]) -> R,
+    {
+        // Safety: We have a `&mut self` and have unique access to the peripheral.
+        unsafe { Rx::<I, M, Fifo0>::conjure().receive(receive) }
+    }
+
+    /// Returns a received frame from FIFO_1 if available.
+    #[inline]
+    pub fn receive1<RECV, R>(
+        &mut self,
+        receive: &mut RECV,
+    ) -> nb::Result<ReceiveOverrun<R>, Infallible>
+    where
+        RECV: FnMut(RxFrameInfo, &[u32]) -> R,
+    {
+        // Safety: We have a `&mut self` and have unique access to the peripheral.
+        unsafe { Rx::<I, M, Fifo1>::conjure().receive(receive) }
+    }
+
+    /// Returns a received frame from `fifo` if available.
+    ///
+    /// This selects the FIFO at runtime, use `receive0` or `receive1` when it is known up front.
+    #[inline]
+    pub fn receive<RECV, R>(
+        &mut self,
+        fifo: Fifo,
+        receive: &mut RECV,
+    ) -> nb::Result<ReceiveOverrun<R>, Infallible>
+    where
+        RECV: FnMut(RxFrameInfo, &[u32]) -> R,
+    {
+        match fifo {
+            Fifo::_0 => self.receive0(receive),
+            Fifo::_1 => self.receive1(receive),
+        }
+    }
+}
+
+/// Control half of `FdCan::split_rx`
+///
+/// Offers the same methods as [`FdCanControl`], but has its own type so it can only be given
+/// back with `FdCan::combine_rx`, which does not take a `Tx` half.
+pub struct FdCanRxControl<I, MODE>(FdCanControl<I, MODE>)
+where
+    I: Instance;
+
+impl<I, MODE> Deref for FdCanRxControl<I, MODE>
+where
+    I: Instance,
+{
+    type Target = FdCanControl<I, MODE>;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<I, MODE> DerefMut for FdCanRxControl<I, MODE>
+where
+    I: Instance,
+{
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// FdCanControl Struct
+/// Used to house some information during an FdCan split.
+/// and can be used for some generic information retrieval during operation.
+pub struct FdCanControl<I, MODE>
+where
+    I: Instance,
+{
+    config: FdCanConfig,
+    instance: I,
+    /// Set while receiving is disabled, see `FdCan::set_receive_enabled`
+    receive_paused: bool,
+    _mode: PhantomData<MODE>,
+}
+impl<I, MODE> FdCanControl<I, MODE>
+where
+    I: Instance,
+{
+    #[inline]
+    fn registers(&self) -> &RegisterBlock {
+        unsafe { &*I::REGISTERS }
+    }
+
+    /// Returns the current error counters
+    #[inline]
+    pub fn error_counters(&self) -> ErrorCounters {
+        ErrorCounters::from_ecr(&self.registers().ecr.read())
+    }
+
+    /// Returns a snapshot of the transmit, receive and error state
+    ///
+    /// Each register involved is read once, in quick succession, so the values are more
+    /// consistent than those of the separate getters. As with those, reading the status resets
+    /// the CAN error logging counter and the last error code.
+    pub fn status(&self) -> CanStatus {
+        let can = self.registers();
+        let txbrp = can.txbrp.read();
+        let rxf0s = can.rxf0s.read();
+        let rxf1s = can.rxf1s.read();
+        let ecr = can.ecr.read();
+        let psr = can.psr.read();
+
+        CanStatus {
+            tx_idle: txbrp.trp().bits() == 0,
+            tx_free_level: free_tx_slots(txbrp.trp().bits()),
+            rx0_fill_level: rxf0s.f0fl().bits(),
+            rx1_fill_level: rxf1s.f1fl().bits(),
+            error_counters: ErrorCounters::from_ecr(&ecr),
+            protocol_status: ProtocolStatus::from_psr(&psr),
+        }
+    }
+
+    /// Returns the current FdCan Timestamp counter
+    #[inline]
+    pub fn timestamp(&self) -> u16 {
+        self.registers().tscv.read().tsc().bits()
+    }
+
+    /// Returns the source of the Timestamp counter, as currently set in the TSCC register
+    ///
+    /// Unlike the stored configuration, this reflects direct writes to the register as well.
+    #[inline]
+    pub fn timestamp_source(&self) -> TimestampSource {
+        let tscc = self.registers().tscc.read();
+        TimestampSource::from_tscc(tscc.tss().bits(), tscc.tcp().bits())
+    }
+
+    /// Returns the last error which occurred on the CAN bus, if any
+    ///
+    /// Note that reading the protocol status resets the last error code, so an error is only
+    /// reported once.
+    #[inline]
+    pub fn last_error(&self) -> Option<CanError> {
+        let psr = self.registers().psr.read();
+        if psr.bo().bit_is_set() {
+            Some(CanError::BusOff)
+        } else {
+            CanError::from_lec(LastErrorCode::try_from(psr.lec().bits()).unwrap())
+        }
+    }
+
+    /// Returns `true` if the node is error active: neither error passive nor bus off
+    ///
+    /// Note that reading the protocol status resets the last error code, see `last_error`.
+    #[inline]
+    pub fn is_error_active(&self) -> bool {
+        let psr = self.registers().psr.read();
+        !psr.ep().bit_is_set() && !psr.bo().bit_is_set()
+    }
+
+    /// Returns `true` if at least one of the error counters has reached the warning limit of 96
+    ///
+    /// Note that reading the protocol status resets the last error code, see `last_error`.
+    #[inline]
+    pub fn is_error_warning(&self) -> bool {
+        self.registers().psr.read().ew().bit_is_set()
+    }
+
+    /// Returns `true` if the node is error passive
+    ///
+    /// Note that reading the protocol status resets the last error code, see `last_error`.
+    #[inline]
+    pub fn is_error_passive(&self) -> bool {
+        self.registers().psr.read().ep().bit_is_set()
+    }
+
+    /// Returns the Transmitter Delay Compensation Value (PSR.TDCV)
+    ///
+    /// This is the loop delay from the TX pin to the RX pin, measured by the hardware during
+    /// the last FdCan frame sent with bit rate switching and transceiver delay compensation
+    /// enabled. It is given in periods of the FDCAN kernel clock, divided by the
+    /// [clock divider](config::ClockDivider), and saturates at 127.
+    ///
+    /// Typical CAN FD transceivers have a loop delay of 100 to 250ns, which is 8 to 20 at a
+    /// kernel clock of 80MHz. A value at or near 127 means the measurement is out of range and
+    /// the data bit rate is probably too high for the transceiver.
+    ///
+    /// Note that reading the protocol status resets the last error code, see `last_error`.
+    #[inline]
+    pub fn transmitter_delay_compensation_value(&self) -> u8 {
+        self.registers().psr.read().tdcv().bits()
+    }
+
+    /// Returns the status of the last High Priority Message
+    ///
+    /// This is updated whenever a filter with a High Priority action matches, see
+    /// [`filter::Filter::high_priority`].
+    #[inline]
+    pub fn high_priority_message_status(&self) -> HpmStatus {
+        let hpms = self.registers().hpms.read();
+        HpmStatus {
+            filter_index: hpms.fidx().bits(),
+            extended_filter: hpms.flst().bit_is_set(),
+            storage: hpms.msi().bits().into(),
+            buffer_index: hpms.bidx().bits(),
+        }
+    }
+
+    /// Returns the id of the oldest frame in `fifo`, without releasing it
+    ///
+    /// This peeks at the frame `receive` would return next, which is handy to check the filters
+    /// during bring-up.
+    #[inline]
+    pub fn last_rx_id(&self, fifo: Fifo) -> Option<Id> {
+        let can = self.registers();
+        let (fill_level, get_index) = match fifo {
+            Fifo::_0 => {
+                let status = can.rxf0s.read();
+                (status.f0fl().bits(), status.f0gi().bits())
+            }
+            Fifo::_1 => {
+                let status = can.rxf1s.read();
+                (status.f1fl().bits(), status.f1gi().bits())
+            }
+        };
+        if fill_level == 0 {
+            return None;
+        }
+
+        // Safety: Read-only access to an element the hardware will not touch until it is released
+        let msg_ram = unsafe { &*I::MSG_RAM };
+        let element = &msg_ram.receive[fifo as usize].fxsa[Mailbox::new(get_index) as usize];
+        Some(RxFrameInfo::from(&element.header).id)
+    }
+
+    /// Returns the id of the frame pending for transmission in `mailbox`, if any
+    #[inline]
+    pub fn pending_tx_id(&self, mailbox: Mailbox) -> Option<Id> {
+        let idx: u8 = mailbox.into();
+        if self.registers().txbrp.read().trp().bits() & (1 << idx) == 0 {
+            return None;
+        }
+
+        // Safety: Read-only access to the header of a pending frame
+        let msg_ram = unsafe { &*I::MSG_RAM };
+        Some(TxFrameHeader::from(&msg_ram.transmit.tbsa[mailbox as usize].header).id)
+    }
+
+    /// Returns the number of times frames were lost, on both FIFOs
+    ///
+    /// The hardware does not count lost frames, only flag that at least one was lost since the
+    /// flag was last cleared. The receive functions clear the flag when they see it and count
+    /// one loss event, so this is a lower bound of the number of lost frames. Frames which are
+    /// overwritten in [`RxFifoMode::Overwrite`] are not flagged, so not counted. The count wraps
+    /// around.
+    #[inline]
+    pub fn dropped_frame_count(&self) -> u32 {
+        dropped_frames::<I>().load(Ordering::Relaxed)
+    }
+
+    /// Resets the counter returned by `dropped_frame_count`
+    #[inline]
+    pub fn reset_dropped_frame_count(&mut self) {
+        dropped_frames::<I>().store(0, Ordering::Relaxed);
     }
 
-    /// Returns a received frame from FIFO_1 if available.
+    /// Returns `true` if a message RAM access failure occurred
+    ///
+    /// The Rx handler could not store a received frame in time, in which case the frame is
+    /// lost, or the Tx handler could not read a frame in time, in which case its transmission
+    /// is aborted and the peripheral switched to Restricted Operation Mode. The flag stays set
+    /// until cleared, and can be enabled as [`Interrupt::MsgRamAccessFailure`].
     #[inline]
-    pub fn receive1<RECV, R>(
-        &mut self,
-        receive: &mut RECV,
-    ) -> nb::Result<ReceiveOverrun<R>, Infallible>
-    where
-        RECV: FnMut(RxFrameInfo, &[u32]) -> R,
-    {
-        // Safety: We have a `&mut self` and have unique access to the peripheral.
-        unsafe { Rx::<I, M, Fifo1>::conjure().receive(receive) }
+    pub fn message_ram_access_failed(&self) -> bool {
+        self.registers().ir.read().mraf().bit_is_set()
     }
-}
 
-/// FdCanControl Struct
-/// Used to house some information during an FdCan split.
-/// and can be used for some generic information retrieval during operation.
-pub struct FdCanControl<I, MODE>
-where
-    I: Instance,
-{
-    config: FdCanConfig,
-    instance: I,
-    _mode: PhantomData<MODE>,
-}
-impl<I, MODE> FdCanControl<I, MODE>
-where
-    I: Instance,
-{
+    /// Clears the message RAM access failure flag
+    ///
+    /// After a Tx handler failure, Restricted Operation Mode has to be left separately.
     #[inline]
-    fn registers(&self) -> &RegisterBlock {
-        unsafe { &*I::REGISTERS }
+    pub fn clear_message_ram_access_failure(&mut self) {
+        self.clear_interrupt(Interrupt::MsgRamAccessFailure)
     }
 
-    /// Returns the current error counters
+    /// Returns `true` if a protocol exception event occurred since the protocol status was
+    /// read last
+    ///
+    /// With protocol exception handling enabled, a node receiving a frame in a format it does
+    /// not support, such as an FdCan frame while FdCan is disabled, stops participating and
+    /// waits for the bus to become idle. This typically happens on mixed Classic CAN and FdCan
+    /// buses. There is no interrupt for this event.
+    ///
+    /// The flag is part of the protocol status register, and is cleared by any read of it,
+    /// including this one and [`FdCan::get_protocol_status`]; that also resets the last error
+    /// code.
     #[inline]
-    pub fn error_counters(&self) -> ErrorCounters {
-        let can = self.registers();
-        let cel: u8 = can.ecr.read().cel().bits();
-        let rp: bool = can.ecr.read().rp().bit();
-        let rec: u8 = can.ecr.read().rec().bits();
-        let tec: u8 = can.ecr.read().tec().bits();
+    pub fn protocol_exception_occurred(&self) -> bool {
+        self.registers().psr.read().pxe().bit_is_set()
+    }
 
-        ErrorCounters {
-            can_errors: cel,
-            transmit_err: tec,
-            receive_err: match rp {
-                false => ReceiveErrorOverflow::Normal(rec),
-                true => ReceiveErrorOverflow::Overflow(rec),
-            },
-        }
+    /// Clears the protocol exception event flag, by reading the protocol status register
+    #[inline]
+    pub fn clear_protocol_exception(&mut self) {
+        self.registers().psr.read();
     }
 
-    /// Returns the current FdCan Timestamp counter
+    /// Returns the set of interrupt flags which are currently pending
+    ///
+    /// Flags are set regardless of whether the interrupt is enabled.
     #[inline]
-    pub fn timestamp(&self) -> u16 {
-        self.registers().tscv.read().tsc().bits()
+    pub fn interrupts_pending(&self) -> Interrupts {
+        Interrupts::from_bits_truncate(self.registers().ir.read().bits())
     }
 
     /// Check if the interrupt is triggered
@@ -1197,6 +3072,8 @@ where
     }
 
     /// Clear specified interrupts
+    ///
+    /// The flags are cleared by writing a 1 to them, flags not in `interrupts` are left as is.
     #[inline]
     pub fn clear_interrupts(&mut self, interrupts: Interrupts) {
         let can = self.registers();
@@ -1205,6 +3082,12 @@ where
 }
 
 /// Interface to the CAN transmitter part.
+///
+/// Obtained from [`FdCan::split`] as an owned handle which can be moved into an interrupt.
+///
+/// It is `Send` without an explicit implementation, as it only holds markers of the peripheral
+/// and mode types, which are `Send`. This is sound because `split` hands out a single `Tx` per
+/// instance, which is the only handle accessing the transmit registers until `combine`.
 pub struct Tx<I, MODE> {
     _can: PhantomData<I>,
     _mode: PhantomData<MODE>,
@@ -1259,11 +3142,20 @@ where
     /// frame, which is returned via the closure 'pending'. If 'pending' is called; it's return value
     /// is returned via Option<P>, if it is not, None is returned.
     /// If there are only higher priority frames in the queue, this returns Err::WouldBlock
+    ///
+    /// Returns a `FrameError` if `frame` is not a valid frame, see [`TxFrameHeader::validate`]: a
+    /// remote frame in the FdCan frame format, or a length which does not fit the frame format.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds this panics if `frame` requests an FdCan frame or bit rate switching
+    /// while the configured `FrameTransmissionConfig` does not allow it, as the hardware would
+    /// silently send a different frame.
     pub fn transmit<WTX>(
         &mut self,
         frame: TxFrameHeader,
         write: &mut WTX,
-    ) -> nb::Result<Option<()>, Infallible>
+    ) -> nb::Result<Option<()>, FrameError>
     where
         WTX: FnMut(&mut [u32]),
     {
@@ -1277,14 +3169,43 @@ where
         frame: TxFrameHeader,
         write: &mut WTX,
         pending: &mut PTX,
-    ) -> nb::Result<Option<P>, Infallible>
+    ) -> nb::Result<Option<P>, FrameError>
+    where
+        PTX: FnMut(Mailbox, TxFrameHeader, &[u32]) -> P,
+        WTX: FnMut(&mut [u32]),
+    {
+        self.enqueue(frame, write, pending)
+            .map(|(_, pending_frame)| pending_frame)
+    }
+
+    /// Puts a CAN frame in a transmit mailbox and returns the mailbox used, see `transmit_preserve`.
+    fn enqueue<PTX, WTX, P>(
+        &mut self,
+        frame: TxFrameHeader,
+        write: &mut WTX,
+        pending: &mut PTX,
+    ) -> nb::Result<(Mailbox, Option<P>), FrameError>
     where
         PTX: FnMut(Mailbox, TxFrameHeader, &[u32]) -> P,
         WTX: FnMut(&mut [u32]),
     {
+        frame.validate()?;
+
         let can = self.registers();
         let queue_is_full = self.tx_queue_is_full();
 
+        if cfg!(debug_assertions) {
+            let cccr = can.cccr.read();
+            debug_assert!(
+                frame.frame_format == FrameFormat::Standard || cccr.fdoe().bit(),
+                "FdCan frames are not allowed by the FrameTransmissionConfig"
+            );
+            debug_assert!(
+                !frame.bit_rate_switching || cccr.brse().bit(),
+                "Bit rate switching is not allowed by the FrameTransmissionConfig"
+            );
+        }
+
         let id = frame.into();
 
         // If the queue is full,
@@ -1319,7 +3240,168 @@ where
 
         self.write_mailbox(idx, frame, write);
 
-        Ok(pending_frame)
+        Ok((idx, pending_frame))
+    }
+
+    /// Checks whether the frame in `mailbox` has left the transmit queue.
+    ///
+    /// Returns `Ok` once the mailbox no longer holds a pending frame, which is the case when the
+    /// frame has been transmitted (TXBTO) but also when it has been aborted (TXBCF), and
+    /// `WouldBlock` while the frame is still pending. Once all mailboxes are complete,
+    /// [`Tx::is_idle`] returns `true`.
+    #[inline]
+    pub fn wait_for_completion(&mut self, mailbox: Mailbox) -> nb::Result<(), Infallible> {
+        if self.has_pending_frame(mailbox) {
+            Err(nb::Error::WouldBlock)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// As Transmit, but returns the mailbox the frame has been placed in.
+    ///
+    /// The mailbox can be passed to `abort` or `wait_for_completion` later on.
+    #[inline]
+    pub fn transmit_indexed<WTX>(
+        &mut self,
+        frame: TxFrameHeader,
+        write: &mut WTX,
+    ) -> nb::Result<TransmitResult, FrameError>
+    where
+        WTX: FnMut(&mut [u32]),
+    {
+        self.enqueue(frame, write, &mut |_, _, _| ())
+            .map(|(mailbox, displaced)| TransmitResult {
+                mailbox,
+                displaced: displaced.is_some(),
+            })
+    }
+
+    /// Puts a frame in a free mailbox for a single transmission attempt, on a best effort basis.
+    ///
+    /// The hardware can only disable automatic retransmission for all frames at once, see
+    /// [`FdCan::with_retransmit_changed`]. This emulates it for a single frame: once the frame
+    /// has had its chance on the bus, for instance at the start of the next time slot, pass the
+    /// returned mailbox to [`Tx::finish_single_shot`] to abort it if it is still pending. A
+    /// transmission in progress can not be interrupted, so after lost arbitration or an error the
+    /// frame may still be retried until then.
+    ///
+    /// Unlike `transmit`, no pending frame is displaced; `WouldBlock` is returned while all
+    /// mailboxes are in use.
+    pub fn transmit_single_shot<WTX>(
+        &mut self,
+        frame: TxFrameHeader,
+        write: &mut WTX,
+    ) -> nb::Result<Mailbox, FrameError>
+    where
+        WTX: FnMut(&mut [u32]),
+    {
+        if self.tx_queue_is_full() {
+            return Err(nb::Error::WouldBlock);
+        }
+        self.enqueue(frame, write, &mut |_, _, _| ())
+            .map(|(mailbox, _)| mailbox)
+    }
+
+    /// Ends the transmission attempt of a frame put in `mailbox` by `transmit_single_shot`.
+    ///
+    /// Aborts the frame if it is still pending, after a transmission in progress has ended, and
+    /// returns `true` if the frame has been transmitted.
+    pub fn finish_single_shot(&mut self, mailbox: Mailbox) -> bool {
+        self.abort(mailbox);
+        let idx: u8 = mailbox.into();
+        self.registers().txbto.read().to().bits() & (1 << idx) != 0
+    }
+
+    /// As Transmit, but reports the outcome without the `nb` encoding.
+    ///
+    /// The results map onto those of `transmit` as follows:
+    ///
+    /// * `Ok(None)` is `Ok(TransmitOutcome::Queued)`
+    /// * `Ok(Some(()))` is `Ok(TransmitOutcome::Displaced)`
+    /// * `Err(nb::Error::WouldBlock)` is `Err(TransmitError::Full)`
+    /// * `Err(nb::Error::Other(e))` is `Err(TransmitError::InvalidFrame(e))`
+    #[inline]
+    pub fn try_transmit<WTX>(
+        &mut self,
+        frame: TxFrameHeader,
+        write: &mut WTX,
+    ) -> Result<TransmitOutcome, TransmitError>
+    where
+        WTX: FnMut(&mut [u32]),
+    {
+        match self.transmit(frame, write) {
+            Ok(None) => Ok(TransmitOutcome::Queued),
+            Ok(Some(())) => Ok(TransmitOutcome::Displaced),
+            Err(nb::Error::WouldBlock) => Err(TransmitError::Full),
+            Err(nb::Error::Other(e)) => Err(TransmitError::InvalidFrame(e)),
+        }
+    }
+
+    /// Puts the `frames`, each a header with its payload, in free transmit mailboxes in order.
+    ///
+    /// Stops at the first frame which does not fit and returns the number of frames that were
+    /// queued, so the remainder can be retried later. Unlike `transmit`, pending frames are never
+    /// displaced, which keeps the order of the batch intact.
+    ///
+    /// Payloads are padded with zeroes up to their `len`.
+    ///
+    /// Stops at the first invalid frame and returns a [`BatchError`] with its index, which is
+    /// also the number of frames queued before it.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds this panics under the same conditions as `transmit`.
+    pub fn transmit_all(&mut self, frames: &[(TxFrameHeader, &[u8])]) -> Result<usize, BatchError> {
+        let mut queued = 0;
+        for (index, (header, data)) in frames.iter().enumerate() {
+            if self.tx_queue_is_full() {
+                break;
+            }
+            // A free mailbox is available, so nothing is displaced
+            match self.transmit(*header, &mut |words| pack_words(words, data)) {
+                Ok(_) => queued += 1,
+                Err(nb::Error::WouldBlock) => break,
+                Err(nb::Error::Other(error)) => return Err(BatchError { index, error }),
+            }
+        }
+        Ok(queued)
+    }
+
+    /// As Transmit, but requests a Tx Event FIFO entry tagged with `marker` for this frame.
+    ///
+    /// Once the frame has been transmitted, its [`TxEvent`] can be read with
+    /// [`Tx::read_tx_event`]. Note that no event is generated for frames which are aborted,
+    /// such as a marked frame being displaced by a higher priority frame in
+    /// `transmit_preserve`; the marker is still part of the header handed to `pending`.
+    #[inline]
+    pub fn transmit_with_marker<WTX>(
+        &mut self,
+        mut frame: TxFrameHeader,
+        marker: u8,
+        write: &mut WTX,
+    ) -> nb::Result<Option<()>, FrameError>
+    where
+        WTX: FnMut(&mut [u32]),
+    {
+        frame.marker = Some(marker);
+        self.transmit(frame, write)
+    }
+
+    /// Reads and acknowledges the oldest entry of the Tx Event FIFO, if any.
+    #[inline]
+    pub fn read_tx_event(&mut self) -> Option<TxEvent> {
+        let can = self.registers();
+        let txefs = can.txefs.read();
+        if txefs.effl().bits() == 0 {
+            return None;
+        }
+
+        let idx = txefs.efgi().bits();
+        let event = (&self.tx_msg_ram().efsa[idx as usize]).into();
+        can.txefa.write(|w| unsafe { w.efai().bits(idx) });
+
+        Some(event)
     }
 
     /// Returns if the tx queue is able to accept new messages without having to cancel an existing one
@@ -1328,6 +3410,23 @@ where
         self.registers().txfqs.read().tfqf().bit()
     }
 
+    /// Returns `true` if a frame can be transmitted without having to cancel an existing one,
+    /// the inverse of [`Tx::tx_queue_is_full`]
+    #[inline]
+    pub fn can_transmit(&self) -> bool {
+        !self.tx_queue_is_full()
+    }
+
+    /// Returns the number of free transmit mailboxes
+    ///
+    /// The mailboxes are always used as a priority queue, in which the Tx FIFO free level
+    /// (TXFQS.TFFL) reads as zero; in FIFO mode it would only count the free elements following
+    /// the last one in use. The free mailboxes are counted from the pending requests instead.
+    #[inline]
+    pub fn tx_slots_free(&self) -> u8 {
+        free_tx_slots(self.registers().txbrp.read().trp().bits())
+    }
+
     /// Returns `Ok` when the mailbox is free or if it contains pending frame with a
     /// lower priority (higher ID) than the identifier `id`.
     #[inline]
@@ -1353,14 +3452,24 @@ where
         // Clear mail slot; mainly for debugging purposes.
         tx_ram.tbsa[idx as usize].reset();
 
-        // Calculate length of data in words
-        let data_len = ((tx_header.len as usize) + 3) / 4;
+        // Calculate length of data in words; the header has been validated, so `len` is a length
+        // which can be sent on the bus
+        let data_len = if tx_header.rtr {
+            0
+        } else {
+            (tx_header.len as usize).div_ceil(4)
+        };
 
         //set header section
         tx_ram.tbsa[idx as usize].header.merge(tx_header);
+        #[cfg(feature = "trace")]
+        trace::transmit(tx_header.id, tx_header.len);
 
         //set data
-        let result = transmit(&mut tx_ram.tbsa[idx as usize].data[0..data_len]);
+        let data = &mut tx_ram.tbsa[idx as usize].data[0..data_len];
+        let result = transmit(data);
+        // The closure writes whole words; don't put whatever it left past `len` on the bus
+        clear_padding(data, tx_header.len as usize);
 
         // Set <idx as Mailbox> as ready to transmit
         self.registers()
@@ -1399,6 +3508,12 @@ where
     /// returns `true`.
     #[inline]
     fn abort(&mut self, idx: Mailbox) -> bool {
+        // Without a limit this can not time out
+        self.abort_within(idx, None).unwrap_or(false)
+    }
+
+    #[inline]
+    fn abort_within(&mut self, idx: Mailbox, spins: Option<u32>) -> Result<bool, Timeout> {
         let can = self.registers();
 
         // Check if there is a request pending to abort
@@ -1410,14 +3525,11 @@ where
             can.txbcr.write(|w| unsafe { w.cr().bits(idx) });
 
             // Wait for the abort request to be finished.
-            loop {
-                if can.txbcf.read().cf().bits() & idx != 0 {
-                    // Return false when a transmission has occured
-                    break can.txbto.read().to().bits() & idx == 0;
-                }
-            }
+            spin_until(spins, || can.txbcf.read().cf().bits() & idx != 0)?;
+            // Return false when a transmission has occured
+            Ok(can.txbto.read().to().bits() & idx == 0)
         } else {
-            false
+            Ok(false)
         }
     }
 
@@ -1437,6 +3549,46 @@ where
         can.txbrp.read().trp().bits() == 0x0
     }
 
+    /// Enables or disables the transmission completed interrupt (TXBTIE) of `mailbox`.
+    ///
+    /// This selects which mailboxes raise `Interrupt::TxComplete` when their frame has been
+    /// transmitted, so an interrupt driven transmitter only wakes for the mailboxes it cares
+    /// about. `Interrupt::TxComplete` itself must be enabled as well.
+    #[inline]
+    pub fn set_completion_interrupt(&mut self, mailbox: Mailbox, enabled: bool) {
+        let can = self.registers();
+        let idx: u8 = mailbox.into();
+        let idx = 1u8 << idx;
+
+        can.txbtie.modify(|r, w| {
+            let bits = if enabled {
+                r.tie().bits() | idx
+            } else {
+                r.tie().bits() & !idx
+            };
+            unsafe { w.tie().bits(bits) }
+        });
+    }
+
+    /// Enables or disables the cancellation finished interrupt (TXBCIE) of `mailbox`.
+    ///
+    /// As `set_completion_interrupt`, for `Interrupt::TxCancel` which must be enabled as well.
+    #[inline]
+    pub fn set_cancellation_interrupt(&mut self, mailbox: Mailbox, enabled: bool) {
+        let can = self.registers();
+        let idx: u8 = mailbox.into();
+        let idx = 1u8 << idx;
+
+        can.txbcie.modify(|r, w| {
+            let bits = if enabled {
+                r.cfie().bits() | idx
+            } else {
+                r.cfie().bits() & !idx
+            };
+            unsafe { w.cfie().bits(bits) }
+        });
+    }
+
     /// Clears the transmission complete flag.
     #[inline]
     pub fn clear_transmission_completed_flag(&mut self) {
@@ -1469,6 +3621,16 @@ impl FifoNr for Fifo1 {
     const NR: usize = 1;
 }
 
+/// Result of [`Tx::transmit_indexed`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct TransmitResult {
+    /// The mailbox the frame has been placed in
+    pub mailbox: Mailbox,
+    /// Set if a pending frame with a lower priority was discarded to make room
+    pub displaced: bool,
+}
+
 /// Notes whether an overrun has occurred.
 /// Since both arms contain T, this can be 'unwrap'ed without causing a panic.
 #[derive(Clone, Copy, Debug)]
@@ -1492,6 +3654,12 @@ impl<T> ReceiveOverrun<T> {
 }
 
 /// Interface to the CAN receiver part.
+///
+/// The STM32G4 FDCAN only provides the two Rx FIFOs, it does not have the dedicated Rx buffers
+/// (and the associated NDAT registers) of other M_CAN implementations. To get per-ID
+/// latest-value semantics, route that ID into its own FIFO with a dedicated filter.
+///
+/// As [`Tx`], it is `Send` because `split` hands out a single `Rx` per FIFO.
 pub struct Rx<I, MODE, FIFONR>
 where
     FIFONR: FifoNr,
@@ -1530,7 +3698,9 @@ where
 
     /// Returns a received frame if available.
     ///
-    /// Returns `Err` when a frame was lost due to buffer overrun.
+    /// The first frame received after frames were lost due to buffer overrun is returned as
+    /// [`ReceiveOverrun::Overrun`]. This clears the message lost flag of the FIFO, which is
+    /// also the `RxFifo0MsgLost`/`RxFifo1MsgLost` interrupt flag.
     pub fn receive<RECV, R>(
         &mut self,
         receive: &mut RECV,
@@ -1544,11 +3714,15 @@ where
             let mailbox: &RxFifoElement = &self.rx_msg_ram().fxsa[idx];
 
             let header: RxFrameInfo = (&mailbox.header).into();
-            let word_len = (header.len + 3) / 4;
+            #[cfg(feature = "trace")]
+            trace::receive(header.id, header.len);
+            let word_len = header.len.div_ceil(4);
             let result = Ok(receive(header, &mailbox.data[0..word_len as usize]));
+            let overrun = self.take_overrun();
             self.release_mailbox(mbox);
 
-            if self.has_overrun() {
+            if overrun {
+                dropped_frames::<I>().fetch_add(1, Ordering::Relaxed);
                 result.map(ReceiveOverrun::Overrun)
             } else {
                 result.map(ReceiveOverrun::NoOverrun)
@@ -1558,6 +3732,164 @@ where
         }
     }
 
+    /// Returns a received frame if available, unpacking its payload directly into `buf`.
+    ///
+    /// Returns the header together with the number of bytes written to the start of `buf`. When
+    /// `buf` is shorter than the payload, the frame is left in the fifo and `RxBufTooSmall` is
+    /// returned, so it can be received again with a larger buffer. Lost frames are only counted
+    /// in [`FdCanControl::dropped_frame_count`], use `receive` to see them per frame.
+    pub fn receive_into(
+        &mut self,
+        buf: &mut [u8],
+    ) -> nb::Result<(RxFrameInfo, usize), RxBufTooSmall> {
+        if self.rx_fifo_is_empty() {
+            return Err(nb::Error::WouldBlock);
+        }
+        let mbox = self.get_rx_mailbox();
+        let idx: usize = mbox.into();
+        let mailbox: &RxFifoElement = &self.rx_msg_ram().fxsa[idx];
+
+        let header: RxFrameInfo = (&mailbox.header).into();
+        let len = header.len as usize;
+        if buf.len() < len {
+            return Err(nb::Error::Other(RxBufTooSmall { required: len }));
+        }
+        #[cfg(feature = "trace")]
+        trace::receive(header.id, header.len);
+        for (bytes, word) in buf[..len].chunks_mut(4).zip(mailbox.data.iter()) {
+            bytes.copy_from_slice(&word.to_le_bytes()[..bytes.len()]);
+        }
+
+        if self.take_overrun() {
+            dropped_frames::<I>().fetch_add(1, Ordering::Relaxed);
+        }
+        self.release_mailbox(mbox);
+        Ok((header, len))
+    }
+
+    /// Receives all frames currently in the fifo, calling `receive` for each of them.
+    ///
+    /// Returns the number of frames received. Frames which arrive while draining are received
+    /// as well.
+    pub fn drain<RECV>(&mut self, mut receive: RECV) -> usize
+    where
+        RECV: FnMut(RxFrameInfo, &[u32]),
+    {
+        let mut count = 0;
+        while self.receive(&mut receive).is_ok() {
+            count += 1;
+        }
+        count
+    }
+
+    /// Receives frames into `buf` until the fifo is empty or `buf` is full.
+    ///
+    /// Returns the number of frames written to the start of `buf`, as `Overrun` if any of them
+    /// reported that a frame was lost. This is meant to be called from an interrupt; the
+    /// STM32G4 has no Rx FIFO watermark, use [`Interrupt::RxFifo0Full`] or the new message
+    /// interrupts instead.
+    pub fn drain_to(&mut self, buf: &mut [ReceivedFrame]) -> ReceiveOverrun<usize> {
+        let mut count = 0;
+        let mut overrun = false;
+        for slot in buf.iter_mut() {
+            match self.receive_frame() {
+                Ok(ReceiveOverrun::NoOverrun(frame)) => *slot = frame,
+                Ok(ReceiveOverrun::Overrun(frame)) => {
+                    overrun = true;
+                    *slot = frame;
+                }
+                Err(_) => break,
+            }
+            count += 1;
+        }
+        if overrun {
+            ReceiveOverrun::Overrun(count)
+        } else {
+            ReceiveOverrun::NoOverrun(count)
+        }
+    }
+
+    /// Moves received frames into the queue `q` until the fifo is empty or `q` is full.
+    ///
+    /// Returns the number of frames queued, as `Overrun` if any of them reported that a frame
+    /// was lost. This does not block, so it can be called from an interrupt while the frames are
+    /// consumed elsewhere.
+    #[cfg(feature = "heapless")]
+    pub fn drain_into_queue<const N: usize>(
+        &mut self,
+        q: &mut heapless::spsc::Producer<'_, ReceivedFrame, N>,
+    ) -> ReceiveOverrun<usize> {
+        let mut count = 0;
+        let mut overrun = false;
+        while q.ready() {
+            let frame = match self.receive_frame() {
+                Ok(ReceiveOverrun::NoOverrun(frame)) => frame,
+                Ok(ReceiveOverrun::Overrun(frame)) => {
+                    overrun = true;
+                    frame
+                }
+                Err(_) => break,
+            };
+            // `ready` guarantees there is room for the frame
+            let _ = q.enqueue(frame);
+            count += 1;
+        }
+        if overrun {
+            ReceiveOverrun::Overrun(count)
+        } else {
+            ReceiveOverrun::NoOverrun(count)
+        }
+    }
+
+    /// Returns a received frame if available, copying its payload into a [`ReceivedFrame`].
+    ///
+    /// This is a convenience over [`Rx::receive`]; use the closure based form to avoid the copy.
+    #[inline]
+    pub fn receive_frame(&mut self) -> nb::Result<ReceiveOverrun<ReceivedFrame>, Infallible> {
+        self.receive(&mut |header, data| ReceivedFrame::new(header, data))
+    }
+
+    /// Returns an iterator over the frames currently in the fifo.
+    ///
+    /// The iterator ends once the fifo is empty. Each frame is wrapped in a [`ReceiveOverrun`],
+    /// so lost frames are not hidden.
+    #[inline]
+    pub fn frames(&mut self) -> impl Iterator<Item = ReceiveOverrun<ReceivedFrame>> + '_ {
+        core::iter::from_fn(move || self.receive_frame().ok())
+    }
+
+    /// Waits until a frame has been received.
+    ///
+    /// This enables the new message interrupt of this FIFO and sleeps until woken by
+    /// [`on_interrupt`]. The user must enable the interrupt line this interrupt is routed to,
+    /// unmask it in the NVIC (see [`interrupt_line_vector`]) and call
+    /// `on_interrupt::<I>(line)` from the interrupt service routine of that line.
+    #[cfg(feature = "async")]
+    pub async fn receive_async(&mut self) -> ReceiveOverrun<ReceivedFrame> {
+        let interrupt = match FIFONR::NR {
+            0 => Interrupt::RxFifo0NewMsg,
+            1 => Interrupt::RxFifo1NewMsg,
+            _ => unreachable!(),
+        };
+
+        core::future::poll_fn(|cx| {
+            let ils = InterruptLineConfig::from_bits(self.registers().ils.read().bits());
+            waker::<I>(ils.line(interrupt)).register(cx.waker());
+
+            match self.receive_frame() {
+                Ok(frame) => Poll::Ready(frame),
+                Err(nb::Error::WouldBlock) => {
+                    self.registers()
+                        .ie
+                        .modify(|r, w| unsafe { w.bits(r.bits() | interrupt as u32) });
+                    Poll::Pending
+                }
+                Err(nb::Error::Other(e)) => match e {},
+            }
+        })
+        .await
+    }
+
     #[inline]
     fn registers(&self) -> &RegisterBlock {
         unsafe { &*I::REGISTERS }
@@ -1568,14 +3900,24 @@ where
         unsafe { &(&(*I::MSG_RAM).receive)[FIFONR::NR] }
     }
 
+    /// Returns if frames have been lost since the last call, and clears the message lost flag.
+    ///
+    /// The RFnL status bit mirrors the IR.RFnL interrupt flag, which is cleared by writing a 1 to
+    /// it, so each loss is only reported once. In overwrite mode the hardware does not flag lost
+    /// frames.
     #[inline]
-    fn has_overrun(&self) -> bool {
+    fn take_overrun(&mut self) -> bool {
         let can = self.registers();
-        match FIFONR::NR {
-            0 => can.rxf0s.read().rf0l().bit(),
-            1 => can.rxf1s.read().rf1l().bit(),
+        let (lost, flag) = match FIFONR::NR {
+            0 => (can.rxf0s.read().rf0l().bit(), Interrupt::RxFifo0MsgLost),
+            1 => (can.rxf1s.read().rf1l().bit(), Interrupt::RxFifo1MsgLost),
             _ => unreachable!(),
+        };
+        if lost {
+            // Safety: Write-1-to-clear of this FIFO's message lost flag only.
+            can.ir.write(|w| unsafe { w.bits(flag as u32) });
         }
+        lost
     }
 
     /// Returns if the fifo contains any new messages.
@@ -1615,6 +3957,16 @@ where
     }
 }
 
+/// The two receive FIFOs, for selecting one at runtime
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum Fifo {
+    /// Receive FIFO 0
+    _0 = 0,
+    /// Receive FIFO 1
+    _1 = 1,
+}
+
 /// The three mailboxes.
 /// These are used for the transmit queue
 /// and the two Receive FIFOs
@@ -1651,135 +4003,3 @@ impl From<Mailbox> for usize {
         m as u8 as usize
     }
 }
-
-mod impls {
-    use super::sealed;
-
-    /// Implements sealed::{Tx,Rx} for pins associated with a CAN peripheral
-    macro_rules! pins {
-        ($PER:ident =>
-            (tx: [ $($( #[ $pmetatx:meta ] )* $tx:ident<$txaf:ident>),+ $(,)? ],
-             rx: [ $($( #[ $pmetarx:meta ] )* $rx:ident<$rxaf:ident>),+ $(,)? ])) => {
-            $(
-                $( #[ $pmetatx ] )*
-                impl super::sealed::Tx<$PER> for $tx<crate::gpio::Alternate<$txaf>> {}
-            )+
-            $(
-                $( #[ $pmetarx ] )*
-                impl super::sealed::Rx<$PER> for $rx<crate::gpio::Alternate<$rxaf>> {}
-            )+
-        };
-    }
-
-    mod fdcan1 {
-        use crate::fdcan;
-        use crate::fdcan::message_ram;
-        use crate::gpio::{
-            gpioa::{PA11, PA12},
-            gpiob::{PB8, PB9},
-            gpiod::{PD0, PD1},
-            AF9,
-        };
-        use crate::stm32;
-        use crate::stm32::FDCAN1;
-
-        // All STM32G4 models with CAN support these pins
-        pins! {
-            FDCAN1 => (
-                tx: [
-                    PA12<AF9>,
-                    PB9<AF9>,
-                    PD1<AF9>,
-                ],
-                rx: [
-                    PA11<AF9>,
-                    PB8<AF9>,
-                    PD0<AF9>,
-                ]
-            )
-        }
-
-        unsafe impl message_ram::MsgRamExt for FDCAN1 {
-            const MSG_RAM: *mut message_ram::RegisterBlock = (0x4000_a400 as *mut _);
-        }
-        unsafe impl fdcan::Instance for FDCAN1 {
-            const REGISTERS: *mut stm32::fdcan::RegisterBlock = FDCAN1::ptr() as *mut _;
-        }
-    }
-
-    #[cfg(any(
-        feature = "stm32g471",
-        feature = "stm32g473",
-        feature = "stm32g474",
-        feature = "stm32g483",
-        feature = "stm32g484",
-        feature = "stm32g491",
-        feature = "stm32g4A1",
-    ))]
-    mod fdcan2 {
-        use crate::fdcan;
-        use crate::fdcan::message_ram;
-        use crate::gpio::{
-            gpiob::{PB12, PB13, PB5, PB6},
-            AF9,
-        };
-        use crate::stm32::{self, FDCAN2};
-
-        pins! {
-            FDCAN2 => (
-                tx: [
-                    PB6<AF9>,
-                    PB13<AF9>,
-                ],
-                rx: [
-                    PB5<AF9>,
-                    PB12<AF9>,
-            ])
-        }
-
-        unsafe impl fdcan::Instance for FDCAN2 {
-            const REGISTERS: *mut stm32::fdcan::RegisterBlock = FDCAN2::ptr() as *mut _;
-        }
-
-        unsafe impl message_ram::MsgRamExt for FDCAN2 {
-            const MSG_RAM: *mut message_ram::RegisterBlock = (0x4000_a750 as *mut _);
-        }
-    }
-
-    #[cfg(any(
-        feature = "stm32g473",
-        feature = "stm32g474",
-        feature = "stm32g483",
-        feature = "stm32g484",
-    ))]
-    mod fdcan3 {
-        use crate::fdcan;
-        use crate::fdcan::message_ram;
-        use crate::gpio::{
-            gpioa::{PA15, PA8},
-            gpiob::{PB3, PB4},
-            AF11,
-        };
-        use crate::stm32::{self, FDCAN3};
-
-        pins! {
-            FDCAN3 => (
-                tx: [
-                    PA15<AF11>,
-                    PB4<AF11>,
-                ],
-                rx: [
-                    PA8<AF11>,
-                    PB3<AF11>,
-            ])
-        }
-
-        unsafe impl fdcan::Instance for FDCAN3 {
-            const REGISTERS: *mut stm32::fdcan::RegisterBlock = FDCAN3::ptr() as *mut _;
-        }
-
-        unsafe impl message_ram::MsgRamExt for FDCAN3 {
-            const MSG_RAM: *mut message_ram::RegisterBlock = (0x4000_aaa0 as *mut _);
-        }
-    }
-}
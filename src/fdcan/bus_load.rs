@@ -0,0 +1,81 @@
+use crate::time::Hertz;
+
+/// Number of bits of a Classic CAN data frame with a standard id and 8 data bytes, including
+/// the interframe space but without stuff bits
+pub const CLASSIC_FRAME_BITS: u32 = 111;
+
+/// Estimates the bus load from the number of frames seen between two timestamps
+///
+/// The hardware does not measure the bus load, so this multiplies the frame count by an
+/// assumed frame length. Call [`BusLoadMonitor::tick`] periodically with the current value of
+/// the Timestamp counter and the number of frames received since the previous call, at least
+/// once per wrap around of the 16 bit counter.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct BusLoadMonitor {
+    tick_rate: u32,
+    bitrate: u32,
+    frame_bits: u32,
+    last_tick: Option<u16>,
+    load: u8,
+}
+impl BusLoadMonitor {
+    /// Creates a monitor for a bus running at `bitrate`, with a Timestamp counter which counts
+    /// at `tick_rate`
+    ///
+    /// With `TimestampSource::Prescaler(p)` the counter runs at `bitrate / p`.
+    pub const fn new(tick_rate: Hertz, bitrate: Hertz) -> Self {
+        Self {
+            tick_rate: tick_rate.0,
+            bitrate: bitrate.0,
+            frame_bits: CLASSIC_FRAME_BITS,
+            last_tick: None,
+            load: 0,
+        }
+    }
+
+    /// Sets the assumed length of a frame in bits, [`CLASSIC_FRAME_BITS`] by default
+    #[must_use]
+    pub const fn with_frame_bits(mut self, bits: u32) -> Self {
+        self.frame_bits = bits;
+        self
+    }
+
+    /// Updates the estimate with the `frames` seen since the previous tick, and returns the
+    /// bus load in percent
+    ///
+    /// The first tick only records `now` and returns the previous estimate.
+    pub fn tick(&mut self, now: u16, frames: u32) -> u8 {
+        if let Some(last) = self.last_tick.replace(now) {
+            let elapsed = u64::from(now.wrapping_sub(last));
+            let available = elapsed * u64::from(self.bitrate);
+            let used = u64::from(frames) * u64::from(self.frame_bits) * u64::from(self.tick_rate);
+            if let Some(load) = (used * 100).checked_div(available) {
+                self.load = load.min(100) as u8;
+            }
+        }
+        self.load
+    }
+
+    /// Returns the last estimated bus load in percent
+    #[inline]
+    pub fn load(&self) -> u8 {
+        self.load
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_estimate() {
+        // 500kbit/s, counting bit times
+        let mut monitor = BusLoadMonitor::new(Hertz(500_000), Hertz(500_000)).with_frame_bits(100);
+        assert_eq!(monitor.tick(0xFF00, 0), 0);
+        // 1000 bit times, of which 500 are used
+        assert_eq!(monitor.tick(0xFF00_u16.wrapping_add(1000), 5), 50);
+        assert_eq!(monitor.tick(0xFF00_u16.wrapping_add(2000), 50), 100);
+        assert_eq!(monitor.load(), 100);
+    }
+}
@@ -0,0 +1,285 @@
+use core::convert::Infallible;
+
+use super::filter::{
+    ExtendedFilter, ExtendedFilterSlot, FilterTarget, FilterType, StandardFilter,
+    StandardFilterSlot, EXTENDED_FILTER_MAX,
+};
+use super::frame::{pack_words, RxFrameInfo, TxFrameHeader};
+use super::id::{ExtendedId, Id, StandardId};
+use super::{FdCan, Fifo, Instance, Receive, Transmit};
+
+/// A Classic CAN data or remote frame, as used by the `bxcan` crate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct Frame {
+    id: Id,
+    rtr: bool,
+    dlc: u8,
+    data: [u8; 8],
+}
+impl Frame {
+    /// Creates a data frame, or returns `None` if `data` is longer than 8 bytes
+    pub fn new_data(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+        if data.len() > 8 {
+            return None;
+        }
+        let mut frame = Self {
+            id: id.into(),
+            rtr: false,
+            dlc: data.len() as u8,
+            data: [0; 8],
+        };
+        frame.data[..data.len()].copy_from_slice(data);
+        Some(frame)
+    }
+
+    /// Creates a remote frame requesting `dlc` bytes, or returns `None` if `dlc` is larger
+    /// than 8
+    pub fn new_remote(id: impl Into<Id>, dlc: u8) -> Option<Self> {
+        if dlc > 8 {
+            return None;
+        }
+        Some(Self {
+            id: id.into(),
+            rtr: true,
+            dlc,
+            data: [0; 8],
+        })
+    }
+
+    /// Rebuilds a frame from a header and the data words of a mailbox
+    fn from_parts(id: Id, rtr: bool, len: u8, words: &[u32]) -> Self {
+        let mut data = [0; 8];
+        for (bytes, word) in data.chunks_exact_mut(4).zip(words) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        Self {
+            id,
+            rtr,
+            dlc: len.min(8),
+            data,
+        }
+    }
+
+    /// Returns the identifier of the frame
+    #[inline]
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    /// Returns `true` if this is a remote frame
+    #[inline]
+    pub fn is_remote_frame(&self) -> bool {
+        self.rtr
+    }
+
+    /// Returns `true` if this is a data frame
+    #[inline]
+    pub fn is_data_frame(&self) -> bool {
+        !self.rtr
+    }
+
+    /// Returns the data length code, the number of data bytes of the frame
+    #[inline]
+    pub fn dlc(&self) -> u8 {
+        self.dlc
+    }
+
+    /// Returns the data of a data frame, or `None` for a remote frame
+    #[inline]
+    pub fn data(&self) -> Option<&[u8]> {
+        match self.rtr {
+            false => Some(&self.data[..self.dlc as usize]),
+            true => None,
+        }
+    }
+
+    fn header(&self) -> TxFrameHeader {
+        match self.rtr {
+            false => TxFrameHeader::new(self.id, self.dlc),
+            // `new_remote` and `from_parts` keep the dlc at 8 or below
+            true => TxFrameHeader {
+                rtr: true,
+                ..TxFrameHeader::new(self.id, self.dlc)
+            },
+        }
+    }
+}
+
+/// An identifier mask filter, as the 32 bit mask filter banks of `bxcan`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum Mask32 {
+    /// Accepts all frames
+    AcceptAll,
+    /// Accepts frames with a standard id which equals `id` in the bits set in `mask`
+    Standard {
+        /// Id to compare with
+        id: StandardId,
+        /// Bits of the id to compare
+        mask: StandardId,
+    },
+    /// Accepts frames with an extended id which equals `id` in the bits set in `mask`
+    Extended {
+        /// Id to compare with
+        id: ExtendedId,
+        /// Bits of the id to compare
+        mask: ExtendedId,
+    },
+}
+impl Mask32 {
+    /// Creates a filter which accepts all frames
+    pub fn accept_all() -> Self {
+        Self::AcceptAll
+    }
+
+    /// Creates a filter which accepts frames with a standard id matching `id` in the bits set
+    /// in `mask`
+    pub fn frames_with_std_id(id: StandardId, mask: StandardId) -> Self {
+        Self::Standard { id, mask }
+    }
+
+    /// Creates a filter which accepts frames with an extended id matching `id` in the bits set
+    /// in `mask`
+    pub fn frames_with_ext_id(id: ExtendedId, mask: ExtendedId) -> Self {
+        Self::Extended { id, mask }
+    }
+}
+
+/// Adapter offering the Classic CAN subset of the `bxcan` API on top of [`FdCan`]
+///
+/// This eases porting code written for the bxcan peripheral of the F-series. It is not a
+/// complete replacement: only frames of up to 8 bytes are handled, and filter banks map onto
+/// the FdCan filter slots as described in [`BxCan::enable_bank`]. The wrapped instance can be
+/// taken back with [`BxCan::free`] to use the full API.
+pub struct BxCan<I, M>
+where
+    I: Instance,
+{
+    can: FdCan<I, M>,
+}
+
+impl<I, M> BxCan<I, M>
+where
+    I: Instance,
+{
+    /// Wraps a configured FdCan instance
+    pub fn new(can: FdCan<I, M>) -> Self {
+        Self { can }
+    }
+
+    /// Returns the wrapped FdCan instance
+    pub fn free(self) -> FdCan<I, M> {
+        self.can
+    }
+
+    /// Configures filter bank `index` to store matching frames in `fifo`
+    ///
+    /// Bank `n` uses standard filter slot `n` and, for banks 0 to 7, extended filter slot `n`;
+    /// the slot which the filter does not use is disabled. An `AcceptAll` filter on banks 8 to
+    /// 27 only accepts standard ids.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not below 28, or if an `Extended` filter is put in bank 8 or above.
+    pub fn enable_bank(&mut self, index: u8, fifo: Fifo, filter: Mask32) {
+        let target = match fifo {
+            Fifo::_0 => FilterTarget::Fifo0,
+            Fifo::_1 => FilterTarget::Fifo1,
+        };
+        let (standard, extended) = match filter {
+            Mask32::AcceptAll => (
+                StandardFilter::accept_all(target),
+                ExtendedFilter::accept_all(target),
+            ),
+            Mask32::Standard { id, mask } => (
+                StandardFilter {
+                    filter: FilterType::BitMask {
+                        filter: id.as_raw(),
+                        mask: mask.as_raw(),
+                    },
+                    action: target.into(),
+                },
+                ExtendedFilter::disable(),
+            ),
+            Mask32::Extended { id, mask } => {
+                assert!(index < EXTENDED_FILTER_MAX, "No extended filter slot");
+                (
+                    StandardFilter::disable(),
+                    ExtendedFilter {
+                        filter: FilterType::BitMask {
+                            filter: id.as_raw(),
+                            mask: mask.as_raw(),
+                        },
+                        action: target.into(),
+                    },
+                )
+            }
+        };
+        self.can
+            .set_standard_filter(StandardFilterSlot::from(index), standard);
+        if index < EXTENDED_FILTER_MAX {
+            self.can
+                .set_extended_filter(ExtendedFilterSlot::from(index), extended);
+        }
+    }
+
+    /// Disables filter bank `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not below 28.
+    pub fn disable_bank(&mut self, index: u8) {
+        self.can
+            .disable_standard_filter(StandardFilterSlot::from(index));
+        if index < EXTENDED_FILTER_MAX {
+            self.can
+                .disable_extended_filter(ExtendedFilterSlot::from(index));
+        }
+    }
+}
+
+impl<I, M> BxCan<I, M>
+where
+    I: Instance,
+    M: Transmit,
+{
+    /// Puts a frame in a free transmit mailbox
+    ///
+    /// As with bxcan, when all mailboxes are full and hold a frame with a lower priority, that
+    /// frame is replaced and returned.
+    pub fn transmit(&mut self, frame: &Frame) -> nb::Result<Option<Frame>, Infallible> {
+        self.can
+            .transmit_preserve(
+                frame.header(),
+                &mut |words| pack_words(words, frame.data().unwrap_or(&[])),
+                &mut |_, header, words| Frame::from_parts(header.id, header.rtr, header.len, words),
+            )
+            .map_err(|e| match e {
+                nb::Error::WouldBlock => nb::Error::WouldBlock,
+                // A `Frame` always holds a valid Classic CAN frame
+                nb::Error::Other(_) => unreachable!(),
+            })
+    }
+}
+
+impl<I, M> BxCan<I, M>
+where
+    I: Instance,
+    M: Receive,
+{
+    /// Returns a received frame from FIFO 0, or from FIFO 1 when FIFO 0 is empty
+    ///
+    /// Unlike bxcan, lost frames are not reported as an error but counted in
+    /// [`FdCan::dropped_frame_count`]. Payloads of FdCan frames are cut to 8 bytes.
+    pub fn receive(&mut self) -> nb::Result<Frame, Infallible> {
+        let mut receive = |info: RxFrameInfo, words: &[u32]| {
+            Frame::from_parts(info.id, info.rtr, info.len, words)
+        };
+        match self.can.receive0(&mut receive) {
+            Err(nb::Error::WouldBlock) => self.can.receive1(&mut receive),
+            result => result,
+        }
+        .map(|frame| frame.unwrap())
+    }
+}
@@ -0,0 +1,996 @@
+pub use super::interrupt::{Interrupt, InterruptLine, Interrupts};
+
+use crate::time::Hertz;
+use core::num::{NonZeroU16, NonZeroU8};
+
+/// Sample point, in per mille of the bit time, used when none is given
+pub const DEFAULT_SAMPLE_POINT: u16 = 875;
+
+/// Finds the smallest prescaler up to `max_prescaler` which divides `clock` into an exact
+/// number of time quanta per bit, and splits them into `(prescaler, seg1, seg2)` around the
+/// `sample_point` in per mille.
+fn calculate_bit_timing(
+    clock: Hertz,
+    bitrate: Hertz,
+    sample_point: u16,
+    max_prescaler: u32,
+    max_seg1: u32,
+    max_seg2: u32,
+) -> Option<(u32, u32, u32)> {
+    if bitrate.0 == 0 || sample_point >= 1000 {
+        return None;
+    }
+    (1..=max_prescaler).find_map(|prescaler| {
+        let tq_clock = prescaler.checked_mul(bitrate.0)?;
+        // One quantum is taken by the synchronization segment
+        let total = clock.0 / tq_clock;
+        if total * tq_clock != clock.0 {
+            return None;
+        }
+        let before_sample = (total * u32::from(sample_point) + 500) / 1000;
+        let seg1 = before_sample.checked_sub(1)?;
+        let seg2 = total - before_sample;
+        if (1..=max_seg1).contains(&seg1) && (1..=max_seg2).contains(&seg2) {
+            Some((prescaler, seg1, seg2))
+        } else {
+            None
+        }
+    })
+}
+
+/// Error returned when a bit timing field is outside of the range supported by the hardware
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum BitTimingError {
+    /// The prescaler is out of range
+    Prescaler,
+    /// Segment 1 is out of range
+    Segment1,
+    /// Segment 2 is out of range
+    Segment2,
+    /// The synchronization jump width is out of range
+    SyncJumpWidth,
+}
+
+/// Configures the bit timings.
+///
+/// You can use <http://www.bittiming.can-wiki.info/> to calculate the `btr` parameter. Enter
+/// parameters as follows:
+///
+/// - *Clock Rate*: The input clock speed to the CAN peripheral (*not* the CPU clock speed).
+///   This is the clock rate of the peripheral bus the CAN peripheral is attached to (eg. APB1).
+/// - *Sample Point*: Should normally be left at the default value of 87.5%.
+/// - *SJW*: Should normally be left at the default value of 1.
+///
+/// Then copy the `CAN_BUS_TIME` register value from the table and pass it as the `btr`
+/// parameter to this method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct NominalBitTiming {
+    /// Value by which the oscillator frequency is divided for generating the bit time quanta. The bit
+    /// time is built up from a multiple of this quanta. Valid values are 1 to 512.
+    pub prescaler: NonZeroU16,
+    /// Valid values are 1 to 256.
+    pub seg1: NonZeroU16,
+    /// Valid values are 1 to 128.
+    pub seg2: NonZeroU8,
+    /// Must not be larger than `seg2`, valid values are 1 to 128.
+    pub sync_jump_width: NonZeroU8,
+}
+impl NominalBitTiming {
+    /// Calculates the timing for `bitrate` with an FDCAN kernel clock of `clock`
+    ///
+    /// `sample_point` is given in per mille of the bit time, see [`DEFAULT_SAMPLE_POINT`]. The
+    /// smallest possible prescaler is used, and the synchronization jump width is set to segment
+    /// 2. Returns `None` if `clock` can not be divided into `bitrate` exactly.
+    pub fn from_bitrate(clock: Hertz, bitrate: Hertz, sample_point: u16) -> Option<Self> {
+        let (prescaler, seg1, seg2) =
+            calculate_bit_timing(clock, bitrate, sample_point, 512, 256, 128)?;
+        Some(Self {
+            prescaler: NonZeroU16::new(prescaler as u16)?,
+            seg1: NonZeroU16::new(seg1 as u16)?,
+            seg2: NonZeroU8::new(seg2 as u8)?,
+            sync_jump_width: NonZeroU8::new(seg2 as u8)?,
+        })
+    }
+
+    /// Checks that all fields are within the range supported by the hardware
+    pub fn validate(&self) -> Result<(), BitTimingError> {
+        if self.prescaler.get() > 512 {
+            Err(BitTimingError::Prescaler)
+        } else if self.seg1.get() > 256 {
+            Err(BitTimingError::Segment1)
+        } else if self.seg2.get() > 128 {
+            Err(BitTimingError::Segment2)
+        } else if self.sync_jump_width.get() > 128 || self.sync_jump_width > self.seg2 {
+            Err(BitTimingError::SyncJumpWidth)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Decodes the value of the NBTP register, see [`FdCan::set_nominal_bit_timing_raw`]
+    ///
+    /// [`FdCan::set_nominal_bit_timing_raw`]: crate::fdcan::FdCan::set_nominal_bit_timing_raw
+    pub(crate) fn from_nbtp(nbtp: u32) -> Self {
+        let field = |shift: u32, mask: u32| ((nbtp >> shift) & mask) + 1;
+        Self {
+            prescaler: NonZeroU16::new(field(16, 0x1FF) as u16).unwrap(),
+            seg1: NonZeroU16::new(field(8, 0xFF) as u16).unwrap(),
+            seg2: NonZeroU8::new(field(0, 0x7F) as u8).unwrap(),
+            sync_jump_width: NonZeroU8::new(field(25, 0x7F) as u8).unwrap(),
+        }
+    }
+
+    /// Encodes the value of the NBTP register
+    pub(crate) fn to_nbtp(self) -> u32 {
+        let field = |value: u32, shift: u32, mask: u32| ((value - 1) & mask) << shift;
+        field(self.nsjw().into(), 25, 0x7F)
+            | field(self.nbrp().into(), 16, 0x1FF)
+            | field(self.ntseg1().into(), 8, 0xFF)
+            | field(self.ntseg2().into(), 0, 0x7F)
+    }
+
+    #[inline]
+    pub(crate) fn nbrp(&self) -> u16 {
+        u16::from(self.prescaler)
+    }
+    #[inline]
+    pub(crate) fn ntseg1(&self) -> u16 {
+        u16::from(self.seg1)
+    }
+    #[inline]
+    pub(crate) fn ntseg2(&self) -> u8 {
+        u8::from(self.seg2)
+    }
+    #[inline]
+    pub(crate) fn nsjw(&self) -> u8 {
+        u8::from(self.sync_jump_width)
+    }
+}
+
+impl Default for NominalBitTiming {
+    #[inline]
+    fn default() -> Self {
+        // Kernel Clock 8MHz, Bit rate: 500kbit/s. Corresponds to a NBTP
+        // register value of 0x0600_0A03
+        Self {
+            prescaler: NonZeroU16::new(1).unwrap(),
+            seg1: NonZeroU16::new(11).unwrap(),
+            seg2: NonZeroU8::new(4).unwrap(),
+            sync_jump_width: NonZeroU8::new(4).unwrap(),
+        }
+    }
+}
+
+/// Configures the data bit timings for the FdCan Variable Bitrates.
+/// This is not used when frame_transmit is set to anything other than AllowFdCanAndBRS.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct DataBitTiming {
+    /// Tranceiver Delay Compensation
+    pub transceiver_delay_compensation: bool,
+    ///  The value by which the oscillator frequency is divided to generate the bit time quanta. The bit
+    ///  time is built up from a multiple of this quanta. Valid values for the Baud Rate Prescaler are 1
+    ///  to 32.
+    pub prescaler: NonZeroU8,
+    /// Valid values are 1 to 32.
+    pub seg1: NonZeroU8,
+    /// Valid values are 1 to 16.
+    pub seg2: NonZeroU8,
+    /// Must not be larger than `seg2`, valid values are 1 to 16.
+    pub sync_jump_width: NonZeroU8,
+}
+impl DataBitTiming {
+    /// Calculates the timing for a data `bitrate` with an FDCAN kernel clock of `clock`
+    ///
+    /// As [`NominalBitTiming::from_bitrate`]. Transceiver delay compensation is left disabled.
+    pub fn from_bitrate(clock: Hertz, bitrate: Hertz, sample_point: u16) -> Option<Self> {
+        let (prescaler, seg1, seg2) =
+            calculate_bit_timing(clock, bitrate, sample_point, 32, 32, 16)?;
+        Some(Self {
+            transceiver_delay_compensation: false,
+            prescaler: NonZeroU8::new(prescaler as u8)?,
+            seg1: NonZeroU8::new(seg1 as u8)?,
+            seg2: NonZeroU8::new(seg2 as u8)?,
+            sync_jump_width: NonZeroU8::new(seg2 as u8)?,
+        })
+    }
+
+    /// Checks that all fields are within the range supported by the hardware
+    pub fn validate(&self) -> Result<(), BitTimingError> {
+        if self.prescaler.get() > 32 {
+            Err(BitTimingError::Prescaler)
+        } else if self.seg1.get() > 32 {
+            Err(BitTimingError::Segment1)
+        } else if self.seg2.get() > 16 {
+            Err(BitTimingError::Segment2)
+        } else if self.sync_jump_width.get() > 16 || self.sync_jump_width > self.seg2 {
+            Err(BitTimingError::SyncJumpWidth)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Decodes the value of the DBTP register, see [`FdCan::set_data_bit_timing_raw`]
+    ///
+    /// [`FdCan::set_data_bit_timing_raw`]: crate::fdcan::FdCan::set_data_bit_timing_raw
+    pub(crate) fn from_dbtp(dbtp: u32) -> Self {
+        let field = |shift: u32, mask: u32| NonZeroU8::new(((dbtp >> shift) & mask) as u8 + 1);
+        Self {
+            transceiver_delay_compensation: dbtp & (1 << 23) != 0,
+            prescaler: field(16, 0x1F).unwrap(),
+            seg1: field(8, 0x1F).unwrap(),
+            seg2: field(4, 0xF).unwrap(),
+            sync_jump_width: field(0, 0xF).unwrap(),
+        }
+    }
+
+    /// Encodes the value of the DBTP register
+    pub(crate) fn to_dbtp(self) -> u32 {
+        let field = |value: u8, shift: u32, mask: u32| ((u32::from(value) - 1) & mask) << shift;
+        u32::from(self.transceiver_delay_compensation) << 23
+            | field(self.dbrp(), 16, 0x1F)
+            | field(self.dtseg1(), 8, 0x1F)
+            | field(self.dtseg2(), 4, 0xF)
+            | field(self.dsjw(), 0, 0xF)
+    }
+
+    #[inline]
+    pub(crate) fn dbrp(&self) -> u8 {
+        u8::from(self.prescaler)
+    }
+    #[inline]
+    pub(crate) fn dtseg1(&self) -> u8 {
+        u8::from(self.seg1)
+    }
+    #[inline]
+    pub(crate) fn dtseg2(&self) -> u8 {
+        u8::from(self.seg2)
+    }
+    #[inline]
+    pub(crate) fn dsjw(&self) -> u8 {
+        u8::from(self.sync_jump_width)
+    }
+}
+
+impl Default for DataBitTiming {
+    #[inline]
+    fn default() -> Self {
+        // Kernel Clock 8MHz, Bit rate: 500kbit/s. Corresponds to a DBTP
+        // register value of 0x0000_0A33
+        Self {
+            transceiver_delay_compensation: false,
+            prescaler: NonZeroU8::new(1).unwrap(),
+            seg1: NonZeroU8::new(11).unwrap(),
+            seg2: NonZeroU8::new(4).unwrap(),
+            sync_jump_width: NonZeroU8::new(4).unwrap(),
+        }
+    }
+}
+
+/// Configures which modes to use
+/// Individual headers can contain a desire to be send via FdCan
+/// or use Bit rate switching. But if this general setting does not allow
+/// that, only classic CAN is used instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum FrameTransmissionConfig {
+    /// Only allow Classic CAN message Frames
+    ClassicCanOnly,
+    /// Allow (non-brs) FdCAN Message Frames
+    AllowFdCan,
+    /// Allow FdCAN Message Frames and allow Bit Rate Switching
+    AllowFdCanAndBRS,
+}
+
+/// Divider of the FdCAN kernel clock
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum ClockDivider {
+    /// Divide by 1
+    _1 = 0b0000,
+    /// Divide by 2
+    _2 = 0b0001,
+    /// Divide by 4
+    _4 = 0b0010,
+    /// Divide by 6
+    _6 = 0b0011,
+    /// Divide by 8
+    _8 = 0b0100,
+    /// Divide by 10
+    _10 = 0b0101,
+    /// Divide by 12
+    _12 = 0b0110,
+    /// Divide by 14
+    _14 = 0b0111,
+    /// Divide by 16
+    _16 = 0b1000,
+    /// Divide by 18
+    _18 = 0b1001,
+    /// Divide by 20
+    _20 = 0b1010,
+    /// Divide by 22
+    _22 = 0b1011,
+    /// Divide by 24
+    _24 = 0b1100,
+    /// Divide by 26
+    _26 = 0b1101,
+    /// Divide by 28
+    _28 = 0b1110,
+    /// Divide by 30
+    _30 = 0b1111,
+}
+
+/// Prescaler of the Timestamp counter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum TimestampPrescaler {
+    /// 1
+    _1 = 1,
+    /// 2
+    _2 = 2,
+    /// 3
+    _3 = 3,
+    /// 4
+    _4 = 4,
+    /// 5
+    _5 = 5,
+    /// 6
+    _6 = 6,
+    /// 7
+    _7 = 7,
+    /// 8
+    _8 = 8,
+    /// 9
+    _9 = 9,
+    /// 10
+    _10 = 10,
+    /// 11
+    _11 = 11,
+    /// 12
+    _12 = 12,
+    /// 13
+    _13 = 13,
+    /// 14
+    _14 = 14,
+    /// 15
+    _15 = 15,
+    /// 16
+    _16 = 16,
+}
+
+/// Selects the source of the Timestamp counter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum TimestampSource {
+    /// The Timestamp counter is disabled
+    None,
+    /// Using the FdCan input clock as the Timstamp counter's source,
+    /// and using a specific prescaler
+    Prescaler(TimestampPrescaler),
+    /// Using TIM3 as a source
+    FromTIM3,
+}
+impl TimestampSource {
+    /// Returns the TSS and TCP fields of the TSCC register for this source
+    pub(crate) fn tscc(self) -> (u8, u8) {
+        match self {
+            Self::None => (0b00, 0),
+            Self::Prescaler(p) => (0b01, p as u8 - 1),
+            Self::FromTIM3 => (0b10, 0),
+        }
+    }
+
+    /// Decodes the TSS and TCP fields of the TSCC register
+    ///
+    /// The reserved TSS encoding keeps the counter at zero, like a disabled counter, so it
+    /// decodes as `None`.
+    pub(crate) fn from_tscc(tss: u8, tcp: u8) -> Self {
+        use TimestampPrescaler::*;
+        const PRESCALERS: [TimestampPrescaler; 16] = [
+            _1, _2, _3, _4, _5, _6, _7, _8, _9, _10, _11, _12, _13, _14, _15, _16,
+        ];
+        match tss {
+            0b01 => Self::Prescaler(PRESCALERS[usize::from(tcp & 0xF)]),
+            0b10 => Self::FromTIM3,
+            _ => Self::None,
+        }
+    }
+}
+
+/// How to handle frames in the global filter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum NonMatchingFilter {
+    /// Frames will go to Fifo0 when they do no match any specific filter
+    IntoRxFifo0 = 0b00,
+    /// Frames will go to Fifo1 when they do no match any specific filter
+    IntoRxFifo1 = 0b01,
+    /// Frames will be rejected when they do not match any specific filter
+    Reject = 0b11,
+}
+
+/// What an Rx FIFO does with new frames when it is full
+///
+/// Blocking mode never loses the frames already in the FIFO, at the cost of dropping new frames
+/// until the application catches up. Overwrite mode always keeps the most recent frames, which
+/// suits latest-value data, but silently replaces the oldest frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum RxFifoMode {
+    /// New frames are discarded while the FIFO is full
+    Blocking = 0,
+    /// New frames overwrite the oldest frame in the FIFO
+    Overwrite = 1,
+}
+
+/// How to handle frames which do not match a specific filter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct GlobalFilter {
+    /// How to handle non-matching standard frames
+    pub handle_standard_frames: NonMatchingFilter,
+
+    /// How to handle non-matching extended frames
+    pub handle_extended_frames: NonMatchingFilter,
+
+    /// How to handle remote standard frames
+    pub reject_remote_standard_frames: bool,
+
+    /// How to handle remote extended frames
+    pub reject_remote_extended_frames: bool,
+}
+impl GlobalFilter {
+    /// Reject all non-matching and remote frames
+    pub const fn reject_all() -> Self {
+        Self {
+            handle_standard_frames: NonMatchingFilter::Reject,
+            handle_extended_frames: NonMatchingFilter::Reject,
+            reject_remote_standard_frames: true,
+            reject_remote_extended_frames: true,
+        }
+    }
+
+    /// How to handle non-matching standard frames
+    pub const fn set_handle_standard_frames(mut self, filter: NonMatchingFilter) -> Self {
+        self.handle_standard_frames = filter;
+        self
+    }
+    /// How to handle non-matching exteded frames
+    pub const fn set_handle_extended_frames(mut self, filter: NonMatchingFilter) -> Self {
+        self.handle_extended_frames = filter;
+        self
+    }
+    /// How to handle remote standard frames
+    pub const fn set_reject_remote_standard_frames(mut self, filter: bool) -> Self {
+        self.reject_remote_standard_frames = filter;
+        self
+    }
+    /// How to handle remote extended frames
+    pub const fn set_reject_remote_extended_frames(mut self, filter: bool) -> Self {
+        self.reject_remote_extended_frames = filter;
+        self
+    }
+}
+impl Default for GlobalFilter {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            handle_standard_frames: NonMatchingFilter::IntoRxFifo0,
+            handle_extended_frames: NonMatchingFilter::IntoRxFifo0,
+            reject_remote_standard_frames: false,
+            reject_remote_extended_frames: false,
+        }
+    }
+}
+
+/// FdCan Config Struct
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct FdCanConfig {
+    /// Nominal Bit Timings
+    pub nbtr: NominalBitTiming,
+    /// (Variable) Data Bit Timings
+    pub dbtr: DataBitTiming,
+    /// Enables or disables automatic retransmission of messages
+    ///
+    /// If this is enabled, the CAN peripheral will automatically try to retransmit each frame
+    /// util it can be sent. Otherwise, it will try only once to send each frame.
+    ///
+    /// Automatic retransmission is enabled by default.
+    pub automatic_retransmit: bool,
+    /// Enabled or disables the pausing between transmissions
+    ///
+    /// This feature looses up burst transmissions coming from a single node and it protects against
+    /// "babbling idiot" scenarios where the application program erroneously requests too many
+    /// transmissions.
+    pub transmit_pause: bool,
+    /// Enabled or disables the pausing between transmissions
+    ///
+    /// This feature looses up burst transmissions coming from a single node and it protects against
+    /// "babbling idiot" scenarios where the application program erroneously requests too many
+    /// transmissions.
+    pub frame_transmit: FrameTransmissionConfig,
+    /// Non Isoe Mode
+    /// If this is set, the FDCAN uses the CAN FD frame format as specified by the Bosch CAN
+    /// FD Specification V1.0.
+    pub non_iso_mode: bool,
+    /// Edge Filtering: Two consecutive dominant tq required to detect an edge for hard synchronization
+    pub edge_filtering: bool,
+    /// Enables protocol exception handling
+    pub protocol_exception_handling: bool,
+    /// Allows [`restrict_on_error`](crate::fdcan::FdCan::restrict_on_error) to move the node
+    /// into restricted operation after a protocol error or protocol exception
+    ///
+    /// This is a software policy, the hardware only enters restricted operation by itself after
+    /// a message RAM access failure. Disabled by default.
+    pub auto_restricted_on_error: bool,
+    /// Sets the general clock divider
+    ///
+    /// This divider is shared by all FdCAN instances, so they should all use the same value.
+    /// Applying a config with a divider other than the one another instance has configured
+    /// fails, see `FdCan::set_clock_divider`.
+    pub clock_divider: ClockDivider,
+    /// This sets the interrupts for each interrupt line of the FdCan (FDCAN_INT0/1)
+    /// Each interrupt set to 0 is set to line_0, each set to 1 is set to line_1.
+    /// NOTE: This does not enable or disable the interrupt, but merely configure
+    /// them to which interrupt the WOULD trigger if they are enabled.
+    pub interrupt_line_config: Interrupts,
+    /// Sets the timestamp source
+    pub timestamp_source: TimestampSource,
+    /// Configures the Global Filter
+    pub global_filter: GlobalFilter,
+    /// Operating mode of Rx FIFO 0
+    pub rx_fifo0_mode: RxFifoMode,
+    /// Operating mode of Rx FIFO 1
+    pub rx_fifo1_mode: RxFifoMode,
+}
+
+impl FdCanConfig {
+    /// Creates a configuration for a Classic CAN bus running at `bitrate`
+    ///
+    /// `clock` is the FDCAN kernel clock. The sample point defaults to 87.5%, or is given in per
+    /// mille by `sample_point`. Returns `None` if the bit timing can not be met, see
+    /// [`NominalBitTiming::from_bitrate`].
+    pub fn classic(clock: Hertz, bitrate: Hertz, sample_point: Option<u16>) -> Option<Self> {
+        let sample_point = sample_point.unwrap_or(DEFAULT_SAMPLE_POINT);
+        let nbtr = NominalBitTiming::from_bitrate(clock, bitrate, sample_point)?;
+        Some(
+            Self::default()
+                .set_nominal_bit_timing(nbtr)
+                .set_frame_transmit(FrameTransmissionConfig::ClassicCanOnly),
+        )
+    }
+
+    /// Creates a configuration for an FdCan bus with bit rate switching, running at `nominal`
+    /// during arbitration and at `data` during the data phase
+    ///
+    /// As [`FdCanConfig::classic`], the sample point applies to both phases.
+    pub fn fd(
+        clock: Hertz,
+        nominal: Hertz,
+        data: Hertz,
+        sample_point: Option<u16>,
+    ) -> Option<Self> {
+        let sample_point = sample_point.unwrap_or(DEFAULT_SAMPLE_POINT);
+        let nbtr = NominalBitTiming::from_bitrate(clock, nominal, sample_point)?;
+        let dbtr = DataBitTiming::from_bitrate(clock, data, sample_point)?;
+        Some(
+            Self::default()
+                .set_nominal_bit_timing(nbtr)
+                .set_data_bit_timing(dbtr)
+                .set_frame_transmit(FrameTransmissionConfig::AllowFdCanAndBRS),
+        )
+    }
+
+    /// Configures the bit timings.
+    #[inline]
+    pub const fn set_nominal_bit_timing(mut self, btr: NominalBitTiming) -> Self {
+        self.nbtr = btr;
+        self
+    }
+
+    /// Configures the bit timings.
+    #[inline]
+    pub const fn set_data_bit_timing(mut self, btr: DataBitTiming) -> Self {
+        self.dbtr = btr;
+        self
+    }
+
+    /// Enables or disables automatic retransmission of messages
+    ///
+    /// If this is enabled, the CAN peripheral will automatically try to retransmit each frame
+    /// util it can be sent. Otherwise, it will try only once to send each frame.
+    ///
+    /// Automatic retransmission is enabled by default.
+    #[inline]
+    pub const fn set_automatic_retransmit(mut self, enabled: bool) -> Self {
+        self.automatic_retransmit = enabled;
+        self
+    }
+
+    /// Enabled or disables the pausing between transmissions
+    ///
+    /// This feature looses up burst transmissions coming from a single node and it protects against
+    /// "babbling idiot" scenarios where the application program erroneously requests too many
+    /// transmissions.
+    #[inline]
+    pub const fn set_transmit_pause(mut self, enabled: bool) -> Self {
+        self.transmit_pause = enabled;
+        self
+    }
+
+    /// If this is set, the FDCAN uses the CAN FD frame format as specified by the Bosch CAN
+    /// FD Specification V1.0.
+    #[inline]
+    pub const fn set_non_iso_mode(mut self, enabled: bool) -> Self {
+        self.non_iso_mode = enabled;
+        self
+    }
+
+    /// Two consecutive dominant tq required to detect an edge for hard synchronization
+    #[inline]
+    pub const fn set_edge_filtering(mut self, enabled: bool) -> Self {
+        self.edge_filtering = enabled;
+        self
+    }
+
+    /// Sets the allowed transmission types for messages.
+    #[inline]
+    pub const fn set_frame_transmit(mut self, fts: FrameTransmissionConfig) -> Self {
+        self.frame_transmit = fts;
+        self
+    }
+
+    /// Selects Interrupt Line 0 or 1 for each interrupt
+    #[inline]
+    pub const fn set_interrupt_line_config(mut self, l0int: Interrupts) -> Self {
+        self.interrupt_line_config = l0int;
+        self
+    }
+
+    /// Enables protocol exception handling
+    #[inline]
+    pub const fn set_protocol_exception_handling(mut self, peh: bool) -> Self {
+        self.protocol_exception_handling = peh;
+        self
+    }
+
+    /// Allows entering restricted operation on protocol errors, see
+    /// [`FdCanConfig::auto_restricted_on_error`]
+    #[inline]
+    pub const fn set_auto_restricted_on_error(mut self, enabled: bool) -> Self {
+        self.auto_restricted_on_error = enabled;
+        self
+    }
+
+    /// Sets the general clock divider, which is shared by all FdCAN instances
+    #[inline]
+    pub const fn set_clock_divider(mut self, div: ClockDivider) -> Self {
+        self.clock_divider = div;
+        self
+    }
+
+    /// Sets the timestamp source
+    #[inline]
+    pub const fn set_timestamp_source(mut self, tss: TimestampSource) -> Self {
+        self.timestamp_source = tss;
+        self
+    }
+
+    /// Sets the global filter settings
+    #[inline]
+    pub const fn set_global_filter(mut self, filter: GlobalFilter) -> Self {
+        self.global_filter = filter;
+        self
+    }
+
+    /// Sets the operating mode of Rx FIFO 0
+    #[inline]
+    pub const fn set_rx_fifo0_mode(mut self, mode: RxFifoMode) -> Self {
+        self.rx_fifo0_mode = mode;
+        self
+    }
+
+    /// Sets the operating mode of Rx FIFO 1
+    #[inline]
+    pub const fn set_rx_fifo1_mode(mut self, mode: RxFifoMode) -> Self {
+        self.rx_fifo1_mode = mode;
+        self
+    }
+}
+
+impl Default for FdCanConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            nbtr: NominalBitTiming::default(),
+            dbtr: DataBitTiming::default(),
+            automatic_retransmit: true,
+            transmit_pause: false,
+            frame_transmit: FrameTransmissionConfig::ClassicCanOnly,
+            non_iso_mode: false,
+            edge_filtering: false,
+            interrupt_line_config: Interrupts::none(),
+            protocol_exception_handling: true,
+            auto_restricted_on_error: false,
+            clock_divider: ClockDivider::_1,
+            timestamp_source: TimestampSource::None,
+            global_filter: GlobalFilter::default(),
+            rx_fifo0_mode: RxFifoMode::Blocking,
+            rx_fifo1_mode: RxFifoMode::Blocking,
+        }
+    }
+}
+
+/// An [`FdCanConfig`] as plain register values, for storing it in flash or EEPROM
+///
+/// Bit timings are kept as register values, so a restored configuration does not depend on
+/// the clock tree at boot. Bit timings which do not pass `validate` are truncated to the width
+/// of their register fields; every other configuration survives the round trip through
+/// [`FdCanConfig::to_raw`] and [`FdCanConfig::from_raw`] unchanged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct RawConfig {
+    /// Value of the NBTP register
+    pub nbtp: u32,
+    /// Value of the DBTP register
+    pub dbtp: u32,
+    /// The NISO, TXP, EFBI, PXHD, BRSE, FDOE and DAR bits of the CCCR register
+    pub cccr: u32,
+    /// Value of the CKDIV register
+    pub ckdiv: u32,
+    /// Value of the TSCC register
+    pub tscc: u32,
+    /// The F0OM, F1OM, ANFS, ANFE, RRFS and RRFE fields of the RXGFC register
+    pub rxgfc: u32,
+    /// Interrupts routed to line 1, in the bit layout of the IE register
+    ///
+    /// Unlike the ILS register, this keeps every interrupt of a group.
+    pub interrupt_line_config: u32,
+    /// Settings without a register: bit 0 is `auto_restricted_on_error`
+    pub software: u32,
+}
+
+const CCCR_NISO: u32 = 1 << 15;
+const CCCR_TXP: u32 = 1 << 14;
+const CCCR_EFBI: u32 = 1 << 13;
+const CCCR_PXHD: u32 = 1 << 12;
+const CCCR_BRSE: u32 = 1 << 9;
+const CCCR_FDOE: u32 = 1 << 8;
+const CCCR_DAR: u32 = 1 << 6;
+
+const RXGFC_F0OM: u32 = 1 << 9;
+const RXGFC_F1OM: u32 = 1 << 8;
+const RXGFC_RRFS: u32 = 1 << 1;
+const RXGFC_RRFE: u32 = 1 << 0;
+
+const SOFTWARE_AUTO_RESTRICTED: u32 = 1 << 0;
+
+impl NonMatchingFilter {
+    fn from_bits(bits: u32) -> Self {
+        match bits & 0b11 {
+            0b00 => Self::IntoRxFifo0,
+            0b01 => Self::IntoRxFifo1,
+            _ => Self::Reject,
+        }
+    }
+}
+
+impl FdCanConfig {
+    /// Returns the register values of this configuration, see [`RawConfig`]
+    pub fn to_raw(&self) -> RawConfig {
+        let bit = |set: bool, bit: u32| if set { bit } else { 0 };
+        let (fdoe, brse) = match self.frame_transmit {
+            FrameTransmissionConfig::ClassicCanOnly => (false, false),
+            FrameTransmissionConfig::AllowFdCan => (true, false),
+            FrameTransmissionConfig::AllowFdCanAndBRS => (true, true),
+        };
+        let (tss, tcp) = self.timestamp_source.tscc();
+        let filter = &self.global_filter;
+
+        RawConfig {
+            nbtp: self.nbtr.to_nbtp(),
+            dbtp: self.dbtr.to_dbtp(),
+            cccr: bit(self.non_iso_mode, CCCR_NISO)
+                | bit(self.transmit_pause, CCCR_TXP)
+                | bit(self.edge_filtering, CCCR_EFBI)
+                | bit(!self.protocol_exception_handling, CCCR_PXHD)
+                | bit(brse, CCCR_BRSE)
+                | bit(fdoe, CCCR_FDOE)
+                | bit(!self.automatic_retransmit, CCCR_DAR),
+            ckdiv: self.clock_divider as u32,
+            tscc: u32::from(tcp) << 16 | u32::from(tss),
+            rxgfc: bit(self.rx_fifo0_mode == RxFifoMode::Overwrite, RXGFC_F0OM)
+                | bit(self.rx_fifo1_mode == RxFifoMode::Overwrite, RXGFC_F1OM)
+                | (filter.handle_standard_frames as u32) << 4
+                | (filter.handle_extended_frames as u32) << 2
+                | bit(filter.reject_remote_standard_frames, RXGFC_RRFS)
+                | bit(filter.reject_remote_extended_frames, RXGFC_RRFE),
+            interrupt_line_config: self.interrupt_line_config.bits(),
+            software: bit(self.auto_restricted_on_error, SOFTWARE_AUTO_RESTRICTED),
+        }
+    }
+
+    /// Restores a configuration from its register values, see [`RawConfig`]
+    ///
+    /// Reserved bits are ignored, and reserved encodings decode as the setting the hardware
+    /// treats them as.
+    pub fn from_raw(raw: RawConfig) -> Self {
+        use ClockDivider::*;
+        const DIVIDERS: [ClockDivider; 16] = [
+            _1, _2, _4, _6, _8, _10, _12, _14, _16, _18, _20, _22, _24, _26, _28, _30,
+        ];
+        let fifo_mode = |overwrite: u32| match raw.rxgfc & overwrite {
+            0 => RxFifoMode::Blocking,
+            _ => RxFifoMode::Overwrite,
+        };
+        let frame_transmit = match (raw.cccr & CCCR_FDOE != 0, raw.cccr & CCCR_BRSE != 0) {
+            (false, _) => FrameTransmissionConfig::ClassicCanOnly,
+            (true, false) => FrameTransmissionConfig::AllowFdCan,
+            (true, true) => FrameTransmissionConfig::AllowFdCanAndBRS,
+        };
+
+        Self {
+            nbtr: NominalBitTiming::from_nbtp(raw.nbtp),
+            dbtr: DataBitTiming::from_dbtp(raw.dbtp),
+            automatic_retransmit: raw.cccr & CCCR_DAR == 0,
+            transmit_pause: raw.cccr & CCCR_TXP != 0,
+            frame_transmit,
+            non_iso_mode: raw.cccr & CCCR_NISO != 0,
+            edge_filtering: raw.cccr & CCCR_EFBI != 0,
+            protocol_exception_handling: raw.cccr & CCCR_PXHD == 0,
+            auto_restricted_on_error: raw.software & SOFTWARE_AUTO_RESTRICTED != 0,
+            clock_divider: DIVIDERS[(raw.ckdiv & 0xF) as usize],
+            interrupt_line_config: Interrupts::from_bits_truncate(raw.interrupt_line_config),
+            timestamp_source: TimestampSource::from_tscc(
+                (raw.tscc & 0b11) as u8,
+                (raw.tscc >> 16 & 0xF) as u8,
+            ),
+            global_filter: GlobalFilter {
+                handle_standard_frames: NonMatchingFilter::from_bits(raw.rxgfc >> 4),
+                handle_extended_frames: NonMatchingFilter::from_bits(raw.rxgfc >> 2),
+                reject_remote_standard_frames: raw.rxgfc & RXGFC_RRFS != 0,
+                reject_remote_extended_frames: raw.rxgfc & RXGFC_RRFE != 0,
+            },
+            rx_fifo0_mode: fifo_mode(RXGFC_F0OM),
+            rx_fifo1_mode: fifo_mode(RXGFC_F1OM),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bit_timing_ranges() {
+        let mut nbtr = NominalBitTiming::default();
+        assert_eq!(nbtr.validate(), Ok(()));
+        nbtr.prescaler = NonZeroU16::new(512).unwrap();
+        assert_eq!(nbtr.validate(), Ok(()));
+        nbtr.prescaler = NonZeroU16::new(513).unwrap();
+        assert_eq!(nbtr.validate(), Err(BitTimingError::Prescaler));
+
+        let mut dbtr = DataBitTiming::default();
+        assert_eq!(dbtr.validate(), Ok(()));
+        dbtr.seg2 = NonZeroU8::new(17).unwrap();
+        assert_eq!(dbtr.validate(), Err(BitTimingError::Segment2));
+
+        let mut dbtr = DataBitTiming::default();
+        dbtr.sync_jump_width = NonZeroU8::new(dbtr.seg2.get() + 1).unwrap();
+        assert_eq!(dbtr.validate(), Err(BitTimingError::SyncJumpWidth));
+        nbtr.prescaler = NonZeroU16::new(1).unwrap();
+        nbtr.sync_jump_width = NonZeroU8::new(nbtr.seg2.get() + 1).unwrap();
+        assert_eq!(nbtr.validate(), Err(BitTimingError::SyncJumpWidth));
+    }
+
+    #[test]
+    fn config_comparison() {
+        let config = FdCanConfig::default();
+        assert_eq!(config, FdCanConfig::default());
+        assert_ne!(config, config.set_clock_divider(ClockDivider::_2));
+        assert_eq!(
+            config.set_frame_transmit(FrameTransmissionConfig::AllowFdCan),
+            config.set_frame_transmit(FrameTransmissionConfig::AllowFdCan)
+        );
+    }
+
+    #[test]
+    fn bit_timing_registers() {
+        assert_eq!(
+            NominalBitTiming::from_nbtp(0x0600_0A03),
+            NominalBitTiming::default()
+        );
+        assert_eq!(
+            DataBitTiming::from_dbtp(0x0000_0A33),
+            DataBitTiming::default()
+        );
+    }
+
+    #[test]
+    fn timestamp_source_registers() {
+        for source in [
+            TimestampSource::None,
+            TimestampSource::Prescaler(TimestampPrescaler::_1),
+            TimestampSource::Prescaler(TimestampPrescaler::_16),
+            TimestampSource::FromTIM3,
+        ] {
+            let (tss, tcp) = source.tscc();
+            assert_eq!(TimestampSource::from_tscc(tss, tcp), source);
+        }
+        assert_eq!(TimestampSource::from_tscc(0b11, 5), TimestampSource::None);
+    }
+
+    #[test]
+    fn raw_config_round_trip() {
+        let config = FdCanConfig::default();
+        let raw = config.to_raw();
+        assert_eq!(raw.nbtp, 0x0600_0A03);
+        assert_eq!(raw.dbtp, 0x0000_0A33);
+        assert_eq!(FdCanConfig::from_raw(raw), config);
+
+        let config = FdCanConfig::fd(Hertz(80_000_000), Hertz(500_000), Hertz(2_000_000), None)
+            .unwrap()
+            .set_automatic_retransmit(false)
+            .set_transmit_pause(true)
+            .set_non_iso_mode(true)
+            .set_edge_filtering(true)
+            .set_protocol_exception_handling(false)
+            .set_auto_restricted_on_error(true)
+            .set_clock_divider(ClockDivider::_30)
+            .set_interrupt_line_config(Interrupts::RX_FIFO_1_NEW_MESSAGE | Interrupts::BUS_OFF)
+            .set_timestamp_source(TimestampSource::Prescaler(TimestampPrescaler::_16))
+            .set_global_filter(
+                GlobalFilter::reject_all()
+                    .set_handle_extended_frames(NonMatchingFilter::IntoRxFifo1),
+            )
+            .set_rx_fifo1_mode(RxFifoMode::Overwrite);
+        assert_eq!(FdCanConfig::from_raw(config.to_raw()), config);
+        assert_eq!(config.to_raw().cccr, 0xF340);
+        assert_eq!(config.to_raw().rxgfc, 0x0137);
+    }
+
+    #[test]
+    fn nominal_segment1_up_to_256() {
+        let nbtr = NominalBitTiming::from_nbtp(0x0000_FF00);
+        assert_eq!(nbtr.seg1.get(), 256);
+        assert_eq!(nbtr.validate(), Ok(()));
+        assert_eq!(nbtr.to_nbtp(), 0x0000_FF00);
+
+        let nbtr = NominalBitTiming {
+            seg1: NonZeroU16::new(257).unwrap(),
+            ..nbtr
+        };
+        assert_eq!(nbtr.validate(), Err(BitTimingError::Segment1));
+    }
+
+    #[test]
+    fn bit_timing_presets() {
+        let config = FdCanConfig::classic(Hertz(80_000_000), Hertz(500_000), None).unwrap();
+        let nbtr = config.nbtr;
+        assert_eq!(nbtr.validate(), Ok(()));
+        assert_eq!((nbtr.nbrp(), nbtr.ntseg1(), nbtr.ntseg2()), (1, 139, 20));
+        assert_eq!(
+            config.frame_transmit,
+            FrameTransmissionConfig::ClassicCanOnly
+        );
+
+        let config = FdCanConfig::fd(
+            Hertz(80_000_000),
+            Hertz(500_000),
+            Hertz(2_000_000),
+            Some(750),
+        )
+        .unwrap();
+        let dbtr = config.dbtr;
+        assert_eq!(dbtr.validate(), Ok(()));
+        assert_eq!((dbtr.dbrp(), dbtr.dtseg1(), dbtr.dtseg2()), (1, 29, 10));
+
+        assert_eq!(
+            FdCanConfig::classic(Hertz(80_000_000), Hertz(470_000), None),
+            None
+        );
+    }
+}
@@ -19,39 +19,48 @@ impl Default for ExtendedFilter {
 }
 
 impl StandardFilter {
-    /// Accept all messages in FIFO 0
-    pub fn accept_all_into_fifo0() -> StandardFilter {
+    /// Match all messages and handle them according to `target`
+    pub fn accept_all(target: FilterTarget) -> StandardFilter {
         StandardFilter {
             filter: FilterType::BitMask {
                 filter: 0x0,
                 mask: 0x0,
             },
-            action: Action::StoreInFifo0,
+            action: target.into(),
         }
     }
 
-    /// Accept all messages in FIFO 1
-    pub fn accept_all_into_fifo1() -> StandardFilter {
+    /// Match messages of which the id equals `filter` in the bits set in `mask`
+    pub fn bit_mask(filter: u16, mask: u16, action: Action) -> StandardFilter {
         StandardFilter {
-            filter: FilterType::BitMask {
-                filter: 0x0,
-                mask: 0x0,
-            },
-            action: Action::StoreInFifo1,
+            filter: FilterType::BitMask { filter, mask },
+            action,
         }
     }
 
-    /// Reject all messages
-    pub fn reject_all() -> StandardFilter {
+    /// Match messages with the id `id`
+    pub fn single(id: StandardId, action: Action) -> StandardFilter {
         StandardFilter {
-            filter: FilterType::BitMask {
-                filter: 0x0,
-                mask: 0x0,
-            },
-            action: Action::Reject,
+            filter: FilterType::DedicatedSingle(id),
+            action,
         }
     }
 
+    /// Accept all messages in FIFO 0
+    pub fn accept_all_into_fifo0() -> StandardFilter {
+        StandardFilter::accept_all(FilterTarget::Fifo0)
+    }
+
+    /// Accept all messages in FIFO 1
+    pub fn accept_all_into_fifo1() -> StandardFilter {
+        StandardFilter::accept_all(FilterTarget::Fifo1)
+    }
+
+    /// Reject all messages
+    pub fn reject_all() -> StandardFilter {
+        StandardFilter::accept_all(FilterTarget::Reject)
+    }
+
     /// Disable the filter
     pub fn disable() -> StandardFilter {
         StandardFilter {
@@ -62,39 +71,48 @@ impl StandardFilter {
 }
 
 impl ExtendedFilter {
-    /// Accept all messages in FIFO 0
-    pub fn accept_all_into_fifo0() -> ExtendedFilter {
+    /// Match all messages and handle them according to `target`
+    pub fn accept_all(target: FilterTarget) -> ExtendedFilter {
         ExtendedFilter {
             filter: FilterType::BitMask {
                 filter: 0x0,
                 mask: 0x0,
             },
-            action: Action::StoreInFifo0,
+            action: target.into(),
         }
     }
 
-    /// Accept all messages in FIFO 1
-    pub fn accept_all_into_fifo1() -> ExtendedFilter {
+    /// Match messages of which the id equals `filter` in the bits set in `mask`
+    pub fn bit_mask(filter: u32, mask: u32, action: Action) -> ExtendedFilter {
         ExtendedFilter {
-            filter: FilterType::BitMask {
-                filter: 0x0,
-                mask: 0x0,
-            },
-            action: Action::StoreInFifo1,
+            filter: FilterType::BitMask { filter, mask },
+            action,
         }
     }
 
-    /// Reject all messages
-    pub fn reject_all() -> ExtendedFilter {
+    /// Match messages with the id `id`
+    pub fn single(id: ExtendedId, action: Action) -> ExtendedFilter {
         ExtendedFilter {
-            filter: FilterType::BitMask {
-                filter: 0x0,
-                mask: 0x0,
-            },
-            action: Action::Reject,
+            filter: FilterType::DedicatedSingle(id),
+            action,
         }
     }
 
+    /// Accept all messages in FIFO 0
+    pub fn accept_all_into_fifo0() -> ExtendedFilter {
+        ExtendedFilter::accept_all(FilterTarget::Fifo0)
+    }
+
+    /// Accept all messages in FIFO 1
+    pub fn accept_all_into_fifo1() -> ExtendedFilter {
+        ExtendedFilter::accept_all(FilterTarget::Fifo1)
+    }
+
+    /// Reject all messages
+    pub fn reject_all() -> ExtendedFilter {
+        ExtendedFilter::accept_all(FilterTarget::Reject)
+    }
+
     /// Disable the filter
     pub fn disable() -> ExtendedFilter {
         ExtendedFilter {
@@ -104,9 +122,21 @@ impl ExtendedFilter {
     }
 }
 
+/// Error returned when more filters are installed than there are filter slots
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct TooManyFilters;
+impl core::fmt::Display for TooManyFilters {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("more filters than filter slots")
+    }
+}
+#[cfg(feature = "core-error")]
+impl core::error::Error for TooManyFilters {}
+
 /// Filter Type
-//#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
 pub enum FilterType<ID, UNIT>
 where
     ID: Copy + Clone + core::fmt::Debug,
@@ -149,8 +179,41 @@ where
     }
 }
 
+/// Where to put messages matched by a filter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum FilterTarget {
+    /// Store matching messages in FIFO 0
+    Fifo0,
+    /// Store matching messages in FIFO 1
+    Fifo1,
+    /// Reject matching messages
+    Reject,
+}
+impl From<FilterTarget> for Action {
+    fn from(target: FilterTarget) -> Self {
+        match target {
+            FilterTarget::Fifo0 => Action::StoreInFifo0,
+            FilterTarget::Fifo1 => Action::StoreInFifo1,
+            FilterTarget::Reject => Action::Reject,
+        }
+    }
+}
+impl From<FilterTarget> for super::config::NonMatchingFilter {
+    fn from(target: FilterTarget) -> Self {
+        match target {
+            FilterTarget::Fifo0 => Self::IntoRxFifo0,
+            FilterTarget::Fifo1 => Self::IntoRxFifo1,
+            FilterTarget::Reject => Self::Reject,
+        }
+    }
+}
+
 /// Filter Action
-#[derive(Clone, Copy, Debug)]
+///
+/// These are all filter element configurations of the STM32G4; the "store into Rx buffer"
+/// configuration of other M_CAN implementations does not exist, as it has no Rx buffers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
 pub enum Action {
     /// No Action
@@ -161,7 +224,7 @@ pub enum Action {
     StoreInFifo1 = 0b010,
     /// Reject an matching message
     Reject = 0b011,
-    /// Flag a matching message (But not store?!?)
+    /// Flag a matching message as a High Priority message, without storing it
     FlagHighPrio = 0b100,
     /// Flag a matching message as a High Priority message and store it in FIFO 0
     FlagHighPrioAndStoreInFifo0 = 0b101,
@@ -181,10 +244,24 @@ impl From<Action> for super::message_ram::enums::FilterElementConfig {
         }
     }
 }
+impl From<super::message_ram::enums::FilterElementConfig> for Action {
+    fn from(fec: super::message_ram::enums::FilterElementConfig) -> Self {
+        use super::message_ram::enums::FilterElementConfig;
+        match fec {
+            FilterElementConfig::DisableFilterElement => Action::Disable,
+            FilterElementConfig::StoreInFifo0 => Action::StoreInFifo0,
+            FilterElementConfig::StoreInFifo1 => Action::StoreInFifo1,
+            FilterElementConfig::Reject => Action::Reject,
+            FilterElementConfig::SetPriority => Action::FlagHighPrio,
+            FilterElementConfig::SetPriorityAndStoreInFifo0 => Action::FlagHighPrioAndStoreInFifo0,
+            FilterElementConfig::SetPriorityAndStoreInFifo1 => Action::FlagHighPrioAndStoreInFifo1,
+        }
+    }
+}
 
 /// Filter
-#[derive(Clone, Copy, Debug)]
-//#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
 pub struct Filter<ID, UNIT>
 where
     ID: Copy + Clone + core::fmt::Debug,
@@ -196,6 +273,27 @@ where
     pub action: Action,
 }
 
+impl<ID, UNIT> Filter<ID, UNIT>
+where
+    ID: Copy + Clone + core::fmt::Debug,
+    UNIT: Copy + Clone + core::fmt::Debug,
+{
+    /// Additionally flag matching messages as High Priority Messages
+    ///
+    /// This raises the High Priority Message interrupt on a match. Where the message has been
+    /// stored can be read back with `FdCanControl::high_priority_message_status`.
+    /// Rejecting filters will flag the message without storing it.
+    pub fn high_priority(mut self) -> Self {
+        self.action = match self.action {
+            Action::StoreInFifo0 => Action::FlagHighPrioAndStoreInFifo0,
+            Action::StoreInFifo1 => Action::FlagHighPrioAndStoreInFifo1,
+            Action::Reject => Action::FlagHighPrio,
+            action => action,
+        };
+        self
+    }
+}
+
 /// Standard Filter Slot
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
@@ -346,22 +444,97 @@ where
     UNIT: Copy + Clone + core::fmt::Debug,
 {
     fn activate(&mut self, f: Filter<ID, UNIT>);
-    // fn read(&self) -> Filter<ID, UNIT>;
+    fn read(&self) -> Filter<ID, UNIT>;
 }
 
 use super::message_ram;
+use super::message_ram::enums::{FilterElementConfig, FilterType as PacFilterType};
 
-impl ActivateFilter<StandardId, u16> for message_ram::StandardFilter {
-    fn activate(&mut self, f: Filter<StandardId, u16>) {
-        let sft = f.filter.into();
+impl StandardFilter {
+    /// Returns the values of the two id fields of the filter element
+    fn to_raw(self) -> (u16, u16) {
+        match self.filter {
+            FilterType::Range { to, from } => (to.as_raw(), from.as_raw()),
+            FilterType::DedicatedSingle(id) => (id.as_raw(), id.as_raw()),
+            FilterType::DedicatedDual(id1, id2) => (id1.as_raw(), id2.as_raw()),
+            FilterType::BitMask { filter, mask } => (filter, mask),
+            FilterType::Disabled => (0x0, 0x0),
+        }
+    }
+
+    /// Decodes the fields of a filter element
+    ///
+    /// A dual id filter with two identical ids is returned as `DedicatedSingle`.
+    fn from_raw(ft: PacFilterType, sfid1: u16, sfid2: u16, fec: FilterElementConfig) -> Self {
+        // Safety: Both id fields are 11 bits wide.
+        let (id1, id2) = unsafe {
+            (
+                StandardId::new_unchecked(sfid1 & 0x7FF),
+                StandardId::new_unchecked(sfid2 & 0x7FF),
+            )
+        };
+        let filter = match ft {
+            PacFilterType::RangeFilter => FilterType::Range { to: id1, from: id2 },
+            PacFilterType::DualIdFilter if sfid1 == sfid2 => FilterType::DedicatedSingle(id1),
+            PacFilterType::DualIdFilter => FilterType::DedicatedDual(id1, id2),
+            PacFilterType::ClassicFilter => FilterType::BitMask {
+                filter: sfid1,
+                mask: sfid2,
+            },
+            PacFilterType::FilterDisabled => FilterType::Disabled,
+        };
+        StandardFilter {
+            filter,
+            action: fec.into(),
+        }
+    }
+}
 
-        let (sfid1, sfid2) = match f.filter {
+impl ExtendedFilter {
+    /// Returns the values of the two id fields of the filter element
+    fn to_raw(self) -> (u32, u32) {
+        match self.filter {
             FilterType::Range { to, from } => (to.as_raw(), from.as_raw()),
             FilterType::DedicatedSingle(id) => (id.as_raw(), id.as_raw()),
             FilterType::DedicatedDual(id1, id2) => (id1.as_raw(), id2.as_raw()),
             FilterType::BitMask { filter, mask } => (filter, mask),
             FilterType::Disabled => (0x0, 0x0),
+        }
+    }
+
+    /// Decodes the fields of a filter element
+    ///
+    /// A dual id filter with two identical ids is returned as `DedicatedSingle`.
+    fn from_raw(ft: PacFilterType, efid1: u32, efid2: u32, fec: FilterElementConfig) -> Self {
+        // Safety: Both id fields are 29 bits wide.
+        let (id1, id2) = unsafe {
+            (
+                ExtendedId::new_unchecked(efid1 & 0x1FFF_FFFF),
+                ExtendedId::new_unchecked(efid2 & 0x1FFF_FFFF),
+            )
+        };
+        let filter = match ft {
+            PacFilterType::RangeFilter => FilterType::Range { to: id1, from: id2 },
+            PacFilterType::DualIdFilter if efid1 == efid2 => FilterType::DedicatedSingle(id1),
+            PacFilterType::DualIdFilter => FilterType::DedicatedDual(id1, id2),
+            PacFilterType::ClassicFilter => FilterType::BitMask {
+                filter: efid1,
+                mask: efid2,
+            },
+            PacFilterType::FilterDisabled => FilterType::Disabled,
         };
+        ExtendedFilter {
+            filter,
+            action: fec.into(),
+        }
+    }
+}
+
+impl ActivateFilter<StandardId, u16> for message_ram::StandardFilter {
+    fn activate(&mut self, f: Filter<StandardId, u16>) {
+        let sft = f.filter.into();
+
+        let (sfid1, sfid2) = f.to_raw();
         let sfec = f.action.into();
         self.write(|w| {
             unsafe { w.sfid1().bits(sfid1).sfid2().bits(sfid2) }
@@ -371,21 +544,21 @@ impl ActivateFilter<StandardId, u16> for message_ram::StandardFilter {
                 .set_filter_element_config(sfec)
         });
     }
-    // fn read(&self) -> Filter<StandardId, u16> {
-    //     todo!()
-    // }
+    fn read(&self) -> Filter<StandardId, u16> {
+        let reader = self.read();
+        StandardFilter::from_raw(
+            reader.sft().to_filter_type(),
+            reader.sfid1().bits(),
+            reader.sfid2().bits(),
+            reader.sfec().to_filter_element_config(),
+        )
+    }
 }
 impl ActivateFilter<ExtendedId, u32> for message_ram::ExtendedFilter {
     fn activate(&mut self, f: Filter<ExtendedId, u32>) {
         let eft = f.filter.into();
 
-        let (efid1, efid2) = match f.filter {
-            FilterType::Range { to, from } => (to.as_raw(), from.as_raw()),
-            FilterType::DedicatedSingle(id) => (id.as_raw(), id.as_raw()),
-            FilterType::DedicatedDual(id1, id2) => (id1.as_raw(), id2.as_raw()),
-            FilterType::BitMask { filter, mask } => (filter, mask),
-            FilterType::Disabled => (0x0, 0x0),
-        };
+        let (efid1, efid2) = f.to_raw();
         let efec = f.action.into();
         self.write(|w| {
             unsafe { w.efid1().bits(efid1).efid2().bits(efid2) }
@@ -395,7 +568,69 @@ impl ActivateFilter<ExtendedId, u32> for message_ram::ExtendedFilter {
                 .set_filter_element_config(efec)
         });
     }
-    // fn read(&self) -> Filter<ExtendedId, u32> {
-    //     todo!()
-    // }
+    fn read(&self) -> Filter<ExtendedId, u32> {
+        let reader = self.read();
+        ExtendedFilter::from_raw(
+            reader.eft().to_filter_type(),
+            reader.efid1().bits(),
+            reader.efid2().bits(),
+            reader.efec().to_filter_element_config(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_filter_round_trip() {
+        let filter = StandardFilter {
+            filter: FilterType::BitMask {
+                filter: 0x120,
+                mask: 0x7F0,
+            },
+            action: Action::StoreInFifo1,
+        };
+        let (sfid1, sfid2) = filter.to_raw();
+        let read =
+            StandardFilter::from_raw(filter.filter.into(), sfid1, sfid2, filter.action.into());
+        assert_eq!(read, filter);
+
+        let single = StandardFilter {
+            filter: FilterType::DedicatedSingle(StandardId::new(0x123).unwrap()),
+            action: Action::FlagHighPrioAndStoreInFifo0,
+        };
+        let (sfid1, sfid2) = single.to_raw();
+        let read =
+            StandardFilter::from_raw(single.filter.into(), sfid1, sfid2, single.action.into());
+        assert_eq!(read, single);
+    }
+
+    #[test]
+    fn filter_actions() {
+        let fifo0 = StandardFilter::bit_mask(0x120, 0x7F0, Action::StoreInFifo0);
+        assert_eq!(
+            fifo0.high_priority(),
+            StandardFilter::bit_mask(0x120, 0x7F0, Action::FlagHighPrioAndStoreInFifo0)
+        );
+        let id = ExtendedId::new(0x1234_5678).unwrap();
+        let reject = ExtendedFilter::single(id, Action::Reject);
+        assert_eq!(reject.high_priority().action, Action::FlagHighPrio);
+    }
+
+    #[test]
+    fn extended_filter_round_trip() {
+        let filter = ExtendedFilter {
+            filter: FilterType::BitMask {
+                filter: 0x18FE_F100,
+                mask: 0x1FFF_FF00,
+            },
+            action: Action::Reject,
+        };
+        let (efid1, efid2) = filter.to_raw();
+        let read =
+            ExtendedFilter::from_raw(filter.filter.into(), efid1, efid2, filter.action.into());
+        assert_eq!(read, filter);
+    }
 }
@@ -0,0 +1,542 @@
+use super::id::{Id, IdReg};
+
+use super::message_ram::enums::FrameFormat as PacFrameFormat;
+use super::message_ram::enums::{
+    DataLength, ErrorStateIndicator, FilterFrameMatch, RemoteTransmissionRequest,
+};
+use super::message_ram::{RxFifoElementHeader, TxBufferElementHeader, TxEventElement};
+
+/// Type of Frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum FrameFormat {
+    /// Frame used by Classic CAN
+    Standard = 0,
+    /// New frame format used by FdCan
+    Fdcan = 1,
+}
+impl From<FrameFormat> for PacFrameFormat {
+    fn from(ff: FrameFormat) -> Self {
+        match ff {
+            FrameFormat::Standard => PacFrameFormat::Standard,
+            FrameFormat::Fdcan => PacFrameFormat::Fdcan,
+        }
+    }
+}
+impl From<PacFrameFormat> for FrameFormat {
+    fn from(ff: PacFrameFormat) -> Self {
+        match ff {
+            PacFrameFormat::Standard => FrameFormat::Standard,
+            PacFrameFormat::Fdcan => FrameFormat::Fdcan,
+        }
+    }
+}
+
+/// Converts a Data Length Code into the length of the payload in bytes
+///
+/// DLC values above 8 are only used by FdCan frames, Classic CAN frames with such a DLC still
+/// carry 8 bytes.
+pub fn dlc_to_len(dlc: u8) -> u8 {
+    match dlc & 0x0F {
+        l @ 0..=8 => l,
+        9 => 12,
+        10 => 16,
+        11 => 20,
+        12 => 24,
+        13 => 32,
+        14 => 48,
+        _ => 64,
+    }
+}
+
+/// Converts a payload length in bytes into a Data Length Code
+///
+/// Returns `None` if `len` is not one of the payload sizes allowed by CAN FD.
+pub fn len_to_dlc(len: u8) -> Option<u8> {
+    match len {
+        0..=8 => Some(len),
+        12 => Some(9),
+        16 => Some(10),
+        20 => Some(11),
+        24 => Some(12),
+        32 => Some(13),
+        48 => Some(14),
+        64 => Some(15),
+        _ => None,
+    }
+}
+
+/// Reasons a [`TxFrameHeader`] does not describe a valid frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub enum FrameError {
+    /// Remote frames only exist in Classic CAN, not in the FdCan frame format
+    RemoteFdFrame,
+    /// The length is above 8 for a Classic CAN frame, or not a valid CAN FD payload size
+    InvalidLength,
+}
+impl core::fmt::Display for FrameError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::RemoteFdFrame => "FdCan frames can not be remote frames",
+            Self::InvalidLength => "invalid length for the frame format",
+        })
+    }
+}
+#[cfg(feature = "core-error")]
+impl core::error::Error for FrameError {}
+
+/// Header of a transmit request
+///
+/// Outside of this crate the header can not be built with a struct literal, so fields can be
+/// added without breaking users. Use [`TxFrameHeader::new`], [`TxFrameHeader::remote`] or
+/// [`TxFrameHeader::reply_to`] with the builder methods instead. This is a breaking change for
+/// code which used struct literals before.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub struct TxFrameHeader {
+    /// Length of the data in bytes
+    pub len: u8,
+    /// Type of message
+    pub frame_format: FrameFormat,
+    /// Id
+    pub id: Id,
+    /// Send a remote frame, requesting `len` bytes of data, instead of a data frame
+    ///
+    /// Remote frames only exist in Classic CAN, see [`TxFrameHeader::remote`].
+    pub rtr: bool,
+    /// Should we use bit rate switching
+    ///
+    /// Not that this is a request and if the global frame_transmit is set to ClassicCanOnly
+    /// this is ignored by the hardware. Debug builds catch this mismatch in `Tx::transmit`.
+    pub bit_rate_switching: bool,
+    /// Marker which is copied into the Tx Event Fifo when set
+    pub marker: Option<u8>,
+    /// Force the Error State Indicator (ESI) to error passive
+    ///
+    /// For normal operation leave this `false`; the hardware then transmits the ESI according
+    /// to its own error state. Only FdCan frames carry an ESI.
+    pub error_state_indicator: bool,
+}
+impl TxFrameHeader {
+    /// Creates the header of a Classic CAN data frame of `len` bytes
+    ///
+    /// Use the builder methods to send it as an FdCan frame, with bit rate switching or with
+    /// a marker.
+    pub fn new(id: Id, len: u8) -> Self {
+        TxFrameHeader {
+            len,
+            frame_format: FrameFormat::Standard,
+            id,
+            rtr: false,
+            bit_rate_switching: false,
+            marker: None,
+            error_state_indicator: false,
+        }
+    }
+
+    /// Creates a header as [`TxFrameHeader::new`], in the given frame format, after checking
+    /// it with [`TxFrameHeader::validate`]
+    pub fn try_new(id: Id, len: u8, frame_format: FrameFormat) -> Result<Self, FrameError> {
+        let header = TxFrameHeader {
+            frame_format,
+            ..Self::new(id, len)
+        };
+        header.validate().map(|_| header)
+    }
+
+    /// Checks that the header describes a frame which can be transmitted
+    ///
+    /// Remote frames must use the Classic CAN frame format and request at most 8 bytes. Classic
+    /// CAN frames carry at most 8 bytes, FdCan frames one of the sizes of [`len_to_dlc`].
+    pub fn validate(&self) -> Result<(), FrameError> {
+        match self.frame_format {
+            FrameFormat::Fdcan if self.rtr => Err(FrameError::RemoteFdFrame),
+            FrameFormat::Standard if self.len > 8 => Err(FrameError::InvalidLength),
+            FrameFormat::Fdcan if len_to_dlc(self.len).is_none() => Err(FrameError::InvalidLength),
+            _ => Ok(()),
+        }
+    }
+
+    /// Sends the frame in the FdCan frame format
+    #[must_use]
+    pub fn fd(mut self) -> Self {
+        self.frame_format = FrameFormat::Fdcan;
+        self
+    }
+
+    /// Requests bit rate switching for the data phase; only applies to FdCan frames
+    #[must_use]
+    pub fn brs(mut self) -> Self {
+        self.bit_rate_switching = true;
+        self
+    }
+
+    /// Sets the marker which is copied into the Tx Event Fifo
+    #[must_use]
+    pub fn with_marker(mut self, marker: u8) -> Self {
+        self.marker = Some(marker);
+        self
+    }
+
+    /// Forces the Error State Indicator to error passive, see
+    /// [`TxFrameHeader::error_state_indicator`]
+    #[must_use]
+    pub fn force_error_passive(mut self) -> Self {
+        self.error_state_indicator = true;
+        self
+    }
+
+    /// Compares the priority of the frames with these headers during bus arbitration.
+    ///
+    /// As [`Id::bus_priority_cmp`], where a data frame additionally wins from a remote frame
+    /// with the same identifier.
+    #[inline]
+    pub fn bus_priority_cmp(&self, other: &TxFrameHeader) -> core::cmp::Ordering {
+        IdReg::from(*other).cmp(&IdReg::from(*self))
+    }
+
+    /// Creates the header of a Classic CAN remote frame, requesting `dlc` bytes of data
+    ///
+    /// No data is transmitted with a remote frame. Returns [`FrameError::InvalidLength`] if
+    /// `dlc` is above 8.
+    pub fn remote(id: Id, dlc: u8) -> Result<Self, FrameError> {
+        let header = TxFrameHeader {
+            rtr: true,
+            ..Self::new(id, dlc)
+        };
+        header.validate().map(|_| header)
+    }
+
+    /// Creates the header of a response to `rx`, using the same id and frame format
+    ///
+    /// The length is copied as well, for a remote frame this is the length of the requested
+    /// data. The response is always a data frame, and no marker is set.
+    pub fn reply_to(rx: &RxFrameInfo) -> Self {
+        TxFrameHeader {
+            rtr: false,
+            ..(*rx).into()
+        }
+    }
+}
+impl From<RxFrameInfo> for TxFrameHeader {
+    fn from(rx: RxFrameInfo) -> Self {
+        TxFrameHeader {
+            len: rx.len,
+            frame_format: rx.frame_format,
+            id: rx.id,
+            rtr: rx.rtr,
+            bit_rate_switching: rx.bit_rate_switching,
+            marker: None,
+            error_state_indicator: false,
+        }
+    }
+}
+impl From<TxFrameHeader> for IdReg {
+    fn from(header: TxFrameHeader) -> IdReg {
+        let id: IdReg = header.id.into();
+        id.with_rtr(header.rtr)
+    }
+}
+
+pub(crate) trait MergeTxFrameHeader {
+    fn merge(&self, header: TxFrameHeader);
+}
+impl MergeTxFrameHeader for TxBufferElementHeader {
+    fn merge(&self, header: TxFrameHeader) {
+        let id: IdReg = header.id.into();
+        self.write(|w| {
+            unsafe { w.id().bits(id.as_raw_id()) }
+                .rtr()
+                .bit(header.rtr)
+                .xtd()
+                .set_id_type(header.id.into())
+                .set_len(DataLength::new(header.len, header.frame_format.into()))
+                .set_event(header.marker.into())
+                .fdf()
+                .set_format(header.frame_format.into())
+                .brs()
+                .bit(header.bit_rate_switching)
+                .esi()
+                .set_error_indicator(if header.error_state_indicator {
+                    ErrorStateIndicator::ErrorPassive
+                } else {
+                    ErrorStateIndicator::ErrorActive
+                })
+        });
+    }
+}
+
+impl From<&TxBufferElementHeader> for TxFrameHeader {
+    fn from(reg: &TxBufferElementHeader) -> Self {
+        let reader = reg.read();
+        let id = reader.id().bits();
+        let rtr = reader.rtr().rtr();
+        let xtd = reader.xtd().id_type();
+        let len = reader.to_data_length();
+        let ff: PacFrameFormat = len.into();
+        TxFrameHeader {
+            len: len.len(),
+            frame_format: ff.into(),
+            id: IdReg::from_register(id, rtr, xtd).into(),
+            rtr: rtr == RemoteTransmissionRequest::TransmitRemoteFrame,
+            bit_rate_switching: reader.brs().is_with_brs(),
+            marker: reader.to_event().into(),
+            error_state_indicator: reader.esi().is_error_passive(),
+        }
+    }
+}
+
+/// Header of a Received Frame
+///
+/// Only this crate creates these, so fields can be added without breaking users. Code outside
+/// of this crate which built it with a struct literal, or destructured it without `..`, has to
+/// be changed.
+#[derive(Copy, Clone)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub struct RxFrameInfo {
+    /// Length in bytes
+    pub len: u8,
+    /// Frame Format
+    pub frame_format: FrameFormat,
+    /// Id
+    pub id: Id,
+    /// Is this an Remote Transmit Request
+    pub rtr: bool,
+    /// Did this message match any filters
+    pub filter_match: Option<u8>,
+    /// was this received with bit rate switching
+    pub bit_rate_switching: bool,
+    /// the time at which the message was received
+    pub time_stamp: u16,
+    /// Was the transmitter error passive (ESI flag); always `false` for Classic CAN frames
+    pub error_state_indicator: bool,
+}
+impl RxFrameInfo {
+    /// Returns the identifier of the frame
+    #[inline]
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    /// Returns `true` if the frame has an extended identifier
+    #[inline]
+    pub fn is_extended(&self) -> bool {
+        matches!(self.id, Id::Extended(_))
+    }
+
+    /// Returns `true` if this is a remote frame
+    #[inline]
+    pub fn is_remote_frame(&self) -> bool {
+        self.rtr
+    }
+
+    /// Returns `true` if the frame uses the FdCan frame format
+    #[inline]
+    pub fn is_fd_frame(&self) -> bool {
+        self.frame_format == FrameFormat::Fdcan
+    }
+
+    /// Returns `true` if the frame was received with bit rate switching
+    #[inline]
+    pub fn bit_rate_switching(&self) -> bool {
+        self.bit_rate_switching
+    }
+
+    /// Returns the length of the payload in bytes
+    #[inline]
+    pub fn len(&self) -> u8 {
+        self.len
+    }
+
+    /// Returns `true` if the transmitter of the frame was error passive
+    #[inline]
+    pub fn is_error_passive(&self) -> bool {
+        self.error_state_indicator
+    }
+
+    /// Returns `true` if the frame carries no payload
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Transforms an RxFrameInfo into an TxFrameHeader
+    pub fn to_tx_header(self, marker: Option<u8>) -> TxFrameHeader {
+        TxFrameHeader {
+            marker,
+            ..self.into()
+        }
+    }
+}
+impl core::fmt::Debug for RxFrameInfo {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RxFrameInfo")
+            .field("id", &self.id)
+            .field("len", &self.len)
+            .field("remote_frame", &self.rtr)
+            .field("fd_frame", &self.is_fd_frame())
+            .field("bit_rate_switching", &self.bit_rate_switching)
+            .field("filter_match", &self.filter_match)
+            .field("time_stamp", &self.time_stamp)
+            .field("error_state_indicator", &self.error_state_indicator)
+            .finish()
+    }
+}
+impl From<&RxFifoElementHeader> for RxFrameInfo {
+    fn from(reg: &RxFifoElementHeader) -> Self {
+        let reader = reg.read();
+        let ff: FrameFormat = reader.fdf().frame_format().into();
+        let len = match ff {
+            FrameFormat::Standard => reader.dlc().bits().min(8),
+            FrameFormat::Fdcan => dlc_to_len(reader.dlc().bits()),
+        };
+        let id = reader.id().bits();
+        let rtr = reader.rtr().rtr();
+        let xtd = reader.xtd().id_type();
+        let id = IdReg::from_register(id, rtr, xtd).to_id();
+        let filter = reader.to_filter_match();
+        let filter = match filter {
+            FilterFrameMatch::DidNotMatch => None,
+            FilterFrameMatch::DidMatch(filter) => Some(filter),
+        };
+        RxFrameInfo {
+            len,
+            frame_format: ff,
+            id,
+            rtr: rtr == RemoteTransmissionRequest::TransmitRemoteFrame,
+            filter_match: filter,
+            bit_rate_switching: reader.brs().is_with_brs(),
+            time_stamp: reader.rxts().bits(),
+            error_state_indicator: reader.esi().is_error_passive(),
+        }
+    }
+}
+
+/// An entry of the Tx Event FIFO, generated for transmitted frames with a marker
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct TxEvent {
+    /// Marker of the transmitted frame, as set in its `TxFrameHeader`
+    pub marker: u8,
+    /// Id of the transmitted frame
+    pub id: Id,
+    /// The time at which the frame was transmitted
+    pub time_stamp: u16,
+}
+impl From<&TxEventElement> for TxEvent {
+    fn from(reg: &TxEventElement) -> Self {
+        let reader = reg.read();
+        let id = reader.id().bits();
+        let rtr = reader.rtr().rtr();
+        let xtd = reader.xtd().id_type();
+        TxEvent {
+            marker: reader.mm().bits(),
+            id: IdReg::from_register(id, rtr, xtd).to_id(),
+            time_stamp: reader.txts().bits(),
+        }
+    }
+}
+
+/// Packs `bytes` into the little-endian data words of a transmit mailbox
+pub(crate) fn pack_words(words: &mut [u32], bytes: &[u8]) {
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks(4)) {
+        let mut le = [0_u8; 4];
+        le[..chunk.len()].copy_from_slice(chunk);
+        *word = u32::from_le_bytes(le);
+    }
+}
+
+/// Clears the bytes past `len` in the data words of a transmit mailbox
+pub(crate) fn clear_padding(words: &mut [u32], len: usize) {
+    for (i, word) in words.iter_mut().enumerate() {
+        let valid = len.saturating_sub(i * 4);
+        if valid < 4 {
+            *word &= !(u32::MAX << (valid * 8));
+        }
+    }
+}
+
+/// A received frame, together with an owned copy of its payload
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct ReceivedFrame {
+    /// Header of the received frame
+    pub header: RxFrameInfo,
+    data: [u8; 64],
+}
+impl ReceivedFrame {
+    /// Unpacks the data words of a receive mailbox into an owned frame
+    pub(crate) fn new(header: RxFrameInfo, words: &[u32]) -> Self {
+        let mut data = [0_u8; 64];
+        for (bytes, word) in data.chunks_exact_mut(4).zip(words) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        Self { header, data }
+    }
+
+    /// Returns the payload of the frame, `header.len` bytes long
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.header.len as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fdcan::id::StandardId;
+
+    #[test]
+    fn dlc_len_round_trip() {
+        for dlc in 0..=15 {
+            assert_eq!(len_to_dlc(dlc_to_len(dlc)), Some(dlc));
+        }
+        assert_eq!(dlc_to_len(9), 12);
+        assert_eq!(dlc_to_len(15), 64);
+        assert_eq!(len_to_dlc(13), None);
+        assert_eq!(len_to_dlc(65), None);
+    }
+
+    #[test]
+    fn illegal_frames_are_rejected() {
+        let id = Id::Standard(StandardId::new(0x123).unwrap());
+        assert!(TxFrameHeader::try_new(id, 8, FrameFormat::Standard).is_ok());
+        assert!(TxFrameHeader::try_new(id, 64, FrameFormat::Fdcan).is_ok());
+        assert_eq!(
+            TxFrameHeader::try_new(id, 12, FrameFormat::Standard).err(),
+            Some(FrameError::InvalidLength)
+        );
+        assert_eq!(
+            TxFrameHeader::try_new(id, 13, FrameFormat::Fdcan).err(),
+            Some(FrameError::InvalidLength)
+        );
+
+        assert!(TxFrameHeader::remote(id, 8).is_ok());
+        assert_eq!(
+            TxFrameHeader::remote(id, 9).err(),
+            Some(FrameError::InvalidLength)
+        );
+        assert_eq!(
+            TxFrameHeader::remote(id, 4).unwrap().fd().validate(),
+            Err(FrameError::RemoteFdFrame)
+        );
+    }
+
+    #[test]
+    fn padding_is_cleared() {
+        let mut words = [u32::MAX; 2];
+        clear_padding(&mut words, 5);
+        assert_eq!(words, [u32::MAX, 0x0000_00FF]);
+
+        let mut words = [u32::MAX; 2];
+        clear_padding(&mut words, 8);
+        assert_eq!(words, [u32::MAX; 2]);
+
+        let mut words = [u32::MAX; 2];
+        clear_padding(&mut words, 0);
+        assert_eq!(words, [0; 2]);
+    }
+}
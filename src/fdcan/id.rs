@@ -1,11 +1,12 @@
 //! CAN Identifiers.
 
 use core::cmp::{Ord, Ordering};
+use core::fmt;
 
 use super::message_ram::enums::{IdType, RemoteTransmissionRequest};
 
 /// Standard 11-bit CAN Identifier (`0..=0x7FF`).
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Copy, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
 pub struct StandardId(u16);
 
@@ -28,6 +29,15 @@ impl StandardId {
         }
     }
 
+    /// Creates a `StandardId` from `ID`, checked at compile time.
+    ///
+    /// An out of range `ID` fails to compile, so no `unwrap` is needed for constant ids:
+    /// `const ID: StandardId = StandardId::new_const::<0x123>();`
+    #[inline]
+    pub const fn new_const<const ID: u16>() -> Self {
+        CheckedStandardId::<ID>::ID
+    }
+
     /// Creates a new `StandardId` without checking if it is inside the valid range.
     ///
     /// # Safety
@@ -45,14 +55,38 @@ impl StandardId {
         self.0
     }
 }
+/// Evaluates the range check of `StandardId::new_const` during compilation
+struct CheckedStandardId<const ID: u16>;
+impl<const ID: u16> CheckedStandardId<ID> {
+    const ID: StandardId = match StandardId::new(ID) {
+        Some(id) => id,
+        None => panic!("Standard CAN id out of range"),
+    };
+}
+
 impl From<StandardId> for IdType {
     fn from(_id: StandardId) -> Self {
         IdType::StandardId
     }
 }
+impl fmt::Debug for StandardId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "StandardId({:#X})", self.0)
+    }
+}
+impl fmt::LowerHex for StandardId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+impl fmt::UpperHex for StandardId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.0, f)
+    }
+}
 
 /// Extended 29-bit CAN Identifier (`0..=1FFF_FFFF`).
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Copy, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
 pub struct ExtendedId(u32);
 
@@ -75,6 +109,15 @@ impl ExtendedId {
         }
     }
 
+    /// Creates an `ExtendedId` from `ID`, checked at compile time.
+    ///
+    /// An out of range `ID` fails to compile, so no `unwrap` is needed for constant ids:
+    /// `const ID: ExtendedId = ExtendedId::new_const::<0x1234_5678>();`
+    #[inline]
+    pub const fn new_const<const ID: u32>() -> Self {
+        CheckedExtendedId::<ID>::ID
+    }
+
     /// Creates a new `ExtendedId` without checking if it is inside the valid range.
     ///
     /// # Safety
@@ -99,14 +142,40 @@ impl ExtendedId {
     }
 }
 
+/// Evaluates the range check of `ExtendedId::new_const` during compilation
+struct CheckedExtendedId<const ID: u32>;
+impl<const ID: u32> CheckedExtendedId<ID> {
+    const ID: ExtendedId = match ExtendedId::new(ID) {
+        Some(id) => id,
+        None => panic!("Extended CAN id out of range"),
+    };
+}
+
 impl From<ExtendedId> for IdType {
     fn from(_id: ExtendedId) -> Self {
         IdType::ExtendedId
     }
 }
+impl fmt::Debug for ExtendedId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ExtendedId({:#X})", self.0)
+    }
+}
+impl fmt::LowerHex for ExtendedId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+impl fmt::UpperHex for ExtendedId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.0, f)
+    }
+}
 
 /// A CAN Identifier (standard or extended).
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+///
+/// `Debug` prints the identifier in hex, as `Std(0x1AB)` or `Ext(0x1234567)`.
+#[derive(Copy, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
 pub enum Id {
     /// Standard 11-bit Identifier (`0..=0x7FF`).
@@ -116,6 +185,51 @@ pub enum Id {
     Extended(ExtendedId),
 }
 
+impl Id {
+    /// Tries to create an `Id` from a raw identifier.
+    ///
+    /// This will return `None` if `raw` is out of range of a standard (`> 0x7FF`) or an
+    /// extended (`> 0x1FFF_FFFF`) identifier.
+    #[inline]
+    pub fn from_raw(raw: u32, extended: bool) -> Option<Self> {
+        if extended {
+            ExtendedId::new(raw).map(Id::Extended)
+        } else if raw <= u32::from(StandardId::MAX.as_raw()) {
+            StandardId::new(raw as u16).map(Id::Standard)
+        } else {
+            None
+        }
+    }
+
+    /// Returns this CAN Identifier as a raw 32-bit integer.
+    #[inline]
+    pub fn as_raw(&self) -> u32 {
+        match self {
+            Id::Standard(id) => u32::from(id.as_raw()),
+            Id::Extended(id) => id.as_raw(),
+        }
+    }
+
+    /// Returns `true` if this is an extended identifier.
+    #[inline]
+    pub fn is_extended(&self) -> bool {
+        matches!(self, Id::Extended(_))
+    }
+
+    /// Compares the priority of data frames with these identifiers during bus arbitration.
+    ///
+    /// `Less` means `self` has a higher priority on the bus, so sorting frames with this puts
+    /// them in the order they would be transmitted. Lower identifiers have a higher priority,
+    /// and a standard identifier wins from an extended identifier with the same base ID.
+    ///
+    /// Use [`TxFrameHeader::bus_priority_cmp`](super::frame::TxFrameHeader::bus_priority_cmp)
+    /// to take remote frames into account.
+    #[inline]
+    pub fn bus_priority_cmp(&self, other: &Id) -> Ordering {
+        IdReg::from(*other).cmp(&IdReg::from(*self))
+    }
+}
+
 impl From<StandardId> for Id {
     #[inline]
     fn from(id: StandardId) -> Self {
@@ -140,23 +254,54 @@ impl From<Id> for IdType {
     }
 }
 
+impl fmt::Debug for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Id::Standard(id) => write!(f, "Std({:#X})", id.as_raw()),
+            Id::Extended(id) => write!(f, "Ext({:#X})", id.as_raw()),
+        }
+    }
+}
+impl fmt::LowerHex for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.as_raw(), f)
+    }
+}
+impl fmt::UpperHex for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.as_raw(), f)
+    }
+}
+
 /// Identifier of a CAN message.
 ///
-/// FdCan be either a standard identifier (11bit, Range: 0..0x3FF) or a
+/// FdCan be either a standard identifier (11bit, Range: 0..0x7FF) or a
 /// extendended identifier (29bit , Range: 0..0x1FFFFFFF).
 ///
+/// The layout matches the first word of the message RAM elements: an extended identifier
+/// occupies bits 0-28, a standard identifier is left-justified in bits 18-28. Bit 29 is the
+/// RTR flag and bit 30 the XTD flag, which marks an extended identifier.
+///
 /// The `Ord` trait can be used to determine the frame’s priority this ID
 /// belongs to.
 /// Lower identifier values have a higher priority. Additionally standard frames
 /// have a higher priority than extended frames and data frames have a higher
 /// priority than remote frames.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
 pub(crate) struct IdReg(u32);
 
+impl fmt::Debug for IdReg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdReg")
+            .field("id", &self.to_id())
+            .field("rtr", &self.rtr())
+            .finish()
+    }
+}
+
 impl IdReg {
     const STANDARD_SHIFT: u32 = 18;
-    #[allow(dead_code)]
     const STANDARD_MASK: u32 = 0x1FFC0000;
 
     const EXTENDED_SHIFT: u32 = 0;
@@ -218,7 +363,7 @@ impl IdReg {
         } else {
             Id::Standard(unsafe {
                 StandardId::new_unchecked(
-                    ((self.0 >> Self::STANDARD_SHIFT) & Self::STANDARD_MASK) as u16,
+                    ((self.0 & Self::STANDARD_MASK) >> Self::STANDARD_SHIFT) as u16,
                 )
             })
         }
@@ -309,3 +454,115 @@ impl PartialOrd for IdReg {
         Some(self.cmp(other))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn standard(raw: u16) -> IdReg {
+        Id::Standard(StandardId::new(raw).unwrap()).into()
+    }
+
+    fn extended(raw: u32) -> IdReg {
+        Id::Extended(ExtendedId::new(raw).unwrap()).into()
+    }
+
+    #[test]
+    fn standard_id_round_trip() {
+        for raw in [0, 0x123, 0x7FF] {
+            let id = Id::Standard(StandardId::new(raw).unwrap());
+            assert_eq!(IdReg::from(id).to_id(), id);
+            assert_eq!(IdReg::from(id).with_rtr(true).to_id(), id);
+        }
+    }
+
+    #[test]
+    fn const_ids() {
+        assert_eq!(StandardId::new_const::<0x7FF>(), StandardId::MAX);
+        assert_eq!(
+            ExtendedId::new_const::<0x1234_5678>(),
+            ExtendedId::new(0x1234_5678).unwrap()
+        );
+    }
+
+    #[test]
+    fn register_layout() {
+        let id = Id::Extended(ExtendedId::new(0x1234_5678).unwrap());
+        let reg = IdReg::from(id);
+        assert_eq!(reg.as_raw_id(), 0x1234_5678);
+        assert!(reg.is_extended());
+        assert_eq!(reg.to_id(), id);
+        assert_eq!(
+            IdReg::from_register(
+                reg.as_raw_id(),
+                RemoteTransmissionRequest::TransmitDataFrame,
+                IdType::ExtendedId
+            )
+            .to_id(),
+            id
+        );
+
+        let id = Id::Standard(StandardId::new(0x123).unwrap());
+        let reg = IdReg::from(id);
+        assert_eq!(reg.as_raw_id(), 0x123 << 18);
+        assert!(reg.is_standard());
+        assert_eq!(
+            IdReg::from_register(
+                reg.as_raw_id(),
+                RemoteTransmissionRequest::TransmitDataFrame,
+                IdType::StandardId
+            )
+            .to_id(),
+            id
+        );
+    }
+
+    #[test]
+    fn raw_id_round_trip() {
+        for (raw, extended) in [(0, false), (0x7FF, false), (0, true), (0x1FFF_FFFF, true)] {
+            let id = Id::from_raw(raw, extended).unwrap();
+            assert_eq!(id.as_raw(), raw);
+            assert_eq!(id.is_extended(), extended);
+        }
+        assert_eq!(Id::from_raw(0x800, false), None);
+        assert_eq!(Id::from_raw(0x2000_0000, true), None);
+    }
+
+    #[test]
+    fn lower_id_has_priority() {
+        assert!(standard(0x100) > standard(0x101));
+        assert!(extended(0x100) > extended(0x101));
+        assert!(standard(0x7FF) < standard(0));
+    }
+
+    #[test]
+    fn data_frame_has_priority_over_remote_frame() {
+        assert!(standard(0x100) > standard(0x100).with_rtr(true));
+        assert!(extended(0x100) > extended(0x100).with_rtr(true));
+        assert!(standard(0x100).with_rtr(true) > standard(0x101));
+    }
+
+    #[test]
+    fn bus_priority_order() {
+        let standard = Id::Standard(StandardId::new(0x123).unwrap());
+        let extended = Id::Extended(ExtendedId::new(0x123 << 18).unwrap());
+        assert_eq!(standard.bus_priority_cmp(&extended), Ordering::Less);
+        assert_eq!(extended.bus_priority_cmp(&standard), Ordering::Greater);
+        assert_eq!(standard.bus_priority_cmp(&standard), Ordering::Equal);
+
+        let lower = Id::Standard(StandardId::new(0x122).unwrap());
+        assert_eq!(lower.bus_priority_cmp(&standard), Ordering::Less);
+    }
+
+    #[test]
+    fn standard_has_priority_over_extended_with_same_base_id() {
+        let base = 0x123 << 18;
+        assert!(standard(0x123) > extended(base));
+        assert!(standard(0x123).with_rtr(true) > extended(base));
+        assert!(standard(0x123) > extended(base | 0x3FFFF));
+        assert!(extended(base) < standard(0x123));
+        // Otherwise the Base ID decides.
+        assert!(extended(0x122 << 18 | 0x3FFFF) > standard(0x123));
+        assert!(standard(0x124) < extended(base | 0x3FFFF));
+    }
+}
@@ -3,7 +3,7 @@
 use core::ops;
 
 #[allow(unused_imports)] // for intra-doc links only
-use crate::fdcan::{FdCan, Rx};
+use crate::fdcan::{FdCan, Rx, Tx};
 
 /// FdCAN interrupt sources.
 ///
@@ -33,16 +33,24 @@ pub enum Interrupt {
     /// A High Priority Message has been flagged by a filter
     RxHighPrio = 1 << 6,
     /// Transmit has been completed
+    ///
+    /// Only raised for the mailboxes selected with [`Tx::set_completion_interrupt`].
     TxComplete = 1 << 7,
     /// Tx message has been cancelled
     TxCancel = 1 << 8,
-    /// Tx Fifo is empty
+    /// Tx Fifo is empty (TFE)
+    ///
+    /// Raised once no frame is pending in any mailbox. An interrupt driven producer can enable
+    /// this to refill all mailboxes at once, or use `TxComplete` to refill one at a time.
     TxEmpty = 1 << 9,
-    /// An new Event has been received in the Tx Event Fifo
+    /// An new Event has been received in the Tx Event Fifo (TEFN)
     TxEventNew = 1 << 10,
-    /// The TxEvent Fifo is full
+    /// The TxEvent Fifo is full (TEFF)
+    ///
+    /// The Tx Event FIFO has a fixed size of 3 elements, so there is no watermark interrupt.
+    /// Read the events with [`Tx::read_tx_event`] before the next one is lost.
     TxEventFull = 1 << 11,
-    /// An Tx Event has been lost
+    /// An Tx Event has been lost (TEFL)
     TxEventLost = 1 << 12,
     /// Timestamp wrap around has occurred
     TsWrapAround = 1 << 13,
@@ -83,6 +91,11 @@ pub enum Interrupt {
 
 bitflags::bitflags! {
     /// A set of FdCAN interrupts.
+    ///
+    /// The bit layout is shared between the interrupt enable (IE) and the interrupt flag (IR)
+    /// registers, so this is used for both enabling and reading back pending interrupts. All 24
+    /// interrupt sources of the STM32G4 are covered; bit errors have no interrupt of their own,
+    /// they are reported through the last error code of the protocol status.
     #[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
     pub struct Interrupts: u32 {
         /// Rx FIFO 0 has a new message
@@ -103,13 +116,13 @@ bitflags::bitflags! {
         const TX_COMPLETE = 1<<7;
         /// Tx message has been cancelled
         const TX_CANCEL = 1<<8;
-        /// Tx Fifo is empty
+        /// Tx Fifo is empty (TFE)
         const TX_EMPTY = 1<<9;
-        /// An new Event has been received in the Tx Event Fifo
+        /// An new Event has been received in the Tx Event Fifo (TEFN)
         const TX_EVENT_NEW = 1<<10;
-        /// The TxEvent Fifo is full
+        /// The TxEvent Fifo is full (TEFF)
         const TX_EVENT_FULL = 1<<11;
-        /// An Tx Event has been lost
+        /// An Tx Event has been lost (TEFL)
         const TX_EVENT_LOST = 1<<12;
         /// Timestamp wrap around has occurred
         const TS_WRAP_AROUND = 1<<13;
@@ -183,6 +196,112 @@ pub enum InterruptLine {
     _1 = 1,
 }
 
+/// Interrupts which are routed together, in the order of the bits of the ILS register
+const LINE_GROUPS: [u32; 7] = [
+    // RXFIFO0: RF0N, RF0F, RF0L
+    0x0000_0007,
+    // RXFIFO1: RF1N, RF1F, RF1L
+    0x0000_0038,
+    // SMSG: HPM, TC, TCF
+    0x0000_01C0,
+    // TFERR: TFE, TEFN, TEFF, TEFL
+    0x0000_1E00,
+    // MISC: TSW, MRAF, TOO
+    0x0000_E000,
+    // BERR: ELO, EP
+    0x0003_0000,
+    // PERR: EW, BO, WDI, PEA, PED, ARA
+    0x00FC_0000,
+];
+
+/// Routing of the interrupts to the two interrupt lines
+///
+/// The FdCAN routes interrupts in groups, so routing an interrupt also routes the other
+/// interrupts of its group (see [`InterruptLineConfig::group`]). By default all interrupts are
+/// routed to line 0.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "unstable-defmt", derive(defmt::Format))]
+pub struct InterruptLineConfig {
+    ils: u32,
+}
+
+impl InterruptLineConfig {
+    /// Routes all interrupts to line 0
+    pub const fn new() -> Self {
+        Self { ils: 0 }
+    }
+
+    /// Routes `interrupt` and the other interrupts of its group to `line`
+    #[must_use]
+    pub fn route(mut self, interrupt: Interrupt, line: InterruptLine) -> Self {
+        let bit = Self::group_index(interrupt as u32);
+        match line {
+            InterruptLine::_0 => self.ils &= !(1 << bit),
+            InterruptLine::_1 => self.ils |= 1 << bit,
+        }
+        self
+    }
+
+    /// Returns the value of the interrupt line select (ILS) register
+    pub fn to_bits(self) -> u32 {
+        self.ils
+    }
+
+    /// Creates the configuration from the value of the interrupt line select (ILS) register
+    ///
+    /// Reserved bits are ignored.
+    pub fn from_bits(ils: u32) -> Self {
+        Self {
+            ils: ils & ((1 << LINE_GROUPS.len()) - 1),
+        }
+    }
+
+    /// Returns the line `interrupt` is routed to
+    pub fn line(self, interrupt: Interrupt) -> InterruptLine {
+        match self.ils & (1 << Self::group_index(interrupt as u32)) {
+            0 => InterruptLine::_0,
+            _ => InterruptLine::_1,
+        }
+    }
+
+    /// Returns all interrupts which are routed to `line`
+    pub fn interrupts_on_line(self, line: InterruptLine) -> Interrupts {
+        let line1 = LINE_GROUPS
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| self.ils & (1 << bit) != 0)
+            .fold(0, |acc, (_, group)| acc | group);
+        match line {
+            InterruptLine::_0 => Interrupts::all() - Interrupts::from_bits_truncate(line1),
+            InterruptLine::_1 => Interrupts::from_bits_truncate(line1),
+        }
+    }
+
+    /// Returns the interrupts which are always routed to the same line as `interrupt`
+    pub fn group(interrupt: Interrupt) -> Interrupts {
+        Interrupts::from_bits_truncate(LINE_GROUPS[Self::group_index(interrupt as u32)])
+    }
+
+    fn group_index(interrupt: u32) -> usize {
+        LINE_GROUPS
+            .iter()
+            .position(|group| group & interrupt != 0)
+            .unwrap()
+    }
+}
+
+/// Routes every group containing one of the interrupts to line 1, the others to line 0.
+impl From<Interrupts> for InterruptLineConfig {
+    fn from(line1: Interrupts) -> Self {
+        let ils = LINE_GROUPS
+            .iter()
+            .enumerate()
+            .filter(|(_, group)| line1.bits() & *group != 0)
+            .fold(0, |acc, (bit, _)| acc | 1 << bit);
+        Self { ils }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,4 +321,69 @@ mod tests {
             Interrupts::RX_FIFO_0_FULL | Interrupts::RX_FIFO_1_FULL
         );
     }
+
+    #[test]
+    fn interrupt_bits_match_reference_manual() {
+        // FDCAN_IR, RM0440
+        let bits = [
+            (Interrupt::RxFifo0NewMsg, 0),
+            (Interrupt::RxFifo0Full, 1),
+            (Interrupt::RxFifo0MsgLost, 2),
+            (Interrupt::RxFifo1NewMsg, 3),
+            (Interrupt::RxFifo1Full, 4),
+            (Interrupt::RxFifo1MsgLost, 5),
+            (Interrupt::RxHighPrio, 6),
+            (Interrupt::TxComplete, 7),
+            (Interrupt::TxCancel, 8),
+            (Interrupt::TxEmpty, 9),
+            (Interrupt::TxEventNew, 10),
+            (Interrupt::TxEventFull, 11),
+            (Interrupt::TxEventLost, 12),
+            (Interrupt::TsWrapAround, 13),
+            (Interrupt::MsgRamAccessFailure, 14),
+            (Interrupt::TimeoutOccurred, 15),
+            (Interrupt::ErrLogOverflow, 16),
+            (Interrupt::ErrPassive, 17),
+            (Interrupt::WarningStatus, 18),
+            (Interrupt::BusOff, 19),
+            (Interrupt::WatchdogInt, 20),
+            (Interrupt::ProtErrArbritation, 21),
+            (Interrupt::ProtErrData, 22),
+            (Interrupt::ReservedAccess, 23),
+        ];
+        let mut all = Interrupts::none();
+        for (interrupt, bit) in bits {
+            assert_eq!(interrupt as u32, 1 << bit);
+            all |= interrupt;
+        }
+        assert_eq!(all, Interrupts::all());
+        assert_eq!(Interrupts::all().bits(), 0x00FF_FFFF);
+    }
+
+    #[test]
+    fn interrupt_line_routing() {
+        let config = InterruptLineConfig::new()
+            .route(Interrupt::RxFifo1NewMsg, InterruptLine::_1)
+            .route(Interrupt::BusOff, InterruptLine::_1);
+        assert_eq!(config.to_bits(), 0b100_0010);
+        assert_eq!(config.line(Interrupt::RxFifo1Full), InterruptLine::_1);
+        assert_eq!(config.line(Interrupt::RxFifo0NewMsg), InterruptLine::_0);
+        assert_eq!(InterruptLineConfig::from_bits(!0).to_bits(), 0b111_1111);
+
+        let line1 = config.interrupts_on_line(InterruptLine::_1);
+        assert!(line1.contains(Interrupts::RX_FIFO_1_MSG_LOST | Interrupts::BUS_OFF));
+        assert!(!line1.contains(Interrupts::RX_FIFO_0_NEW_MESSAGE));
+        assert_eq!(
+            config.interrupts_on_line(InterruptLine::_0),
+            Interrupts::all() - line1
+        );
+
+        let config = config.route(Interrupt::ProtErrData, InterruptLine::_0);
+        assert_eq!(config.to_bits(), 0b000_0010);
+        assert_eq!(
+            InterruptLineConfig::from(Interrupts::RX_FIFO_1_FULL),
+            config
+        );
+        assert!(InterruptLineConfig::group(Interrupt::TxComplete).contains(Interrupts::TX_CANCEL));
+    }
 }
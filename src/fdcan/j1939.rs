@@ -0,0 +1,50 @@
+use super::id::ExtendedId;
+
+impl ExtendedId {
+    /// Creates the identifier of a SAE J1939 (or NMEA 2000) frame.
+    ///
+    /// The 29 bits are made up of the 3 bit `priority`, the 18 bit Parameter Group Number and
+    /// the `source_address`. For PDU1 parameter groups, with a PDU format below 240, the low byte
+    /// of `pgn` holds the destination address.
+    ///
+    /// Returns `None` if `priority` is larger than 7 or `pgn` larger than `0x3FFFF`.
+    pub fn from_j1939(priority: u8, pgn: u32, source_address: u8) -> Option<Self> {
+        if priority > 0x7 || pgn > 0x3_FFFF {
+            return None;
+        }
+        let raw = (u32::from(priority) << 26) | (pgn << 8) | u32::from(source_address);
+        // Safety: The fields add up to 29 bits.
+        Some(unsafe { Self::new_unchecked(raw) })
+    }
+
+    /// Splits the identifier of a SAE J1939 frame into its priority, Parameter Group Number and
+    /// source address.
+    ///
+    /// This is the inverse of [`ExtendedId::from_j1939`], so for PDU1 parameter groups the low
+    /// byte of the PGN is the destination address.
+    pub fn j1939_parts(&self) -> (u8, u32, u8) {
+        let raw = self.as_raw();
+        ((raw >> 26) as u8, (raw >> 8) & 0x3_FFFF, raw as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn j1939_encoding() {
+        // EEC1, PGN 61444, from the engine
+        let eec1 = ExtendedId::from_j1939(3, 61444, 0x00).unwrap();
+        assert_eq!(eec1.as_raw(), 0x0CF0_0400);
+        assert_eq!(eec1.j1939_parts(), (3, 61444, 0x00));
+
+        // Request, PGN 59904, to the global address
+        let request = ExtendedId::new(0x18EA_FFF9).unwrap();
+        assert_eq!(request.j1939_parts(), (6, 0xEAFF, 0xF9));
+        assert_eq!(ExtendedId::from_j1939(6, 0xEAFF, 0xF9), Some(request));
+
+        assert_eq!(ExtendedId::from_j1939(8, 61444, 0x00), None);
+        assert_eq!(ExtendedId::from_j1939(3, 0x4_0000, 0x00), None);
+    }
+}
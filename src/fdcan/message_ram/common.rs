@@ -0,0 +1,144 @@
+use super::enums::{
+    BitRateSwitching, ErrorStateIndicator, FilterElementConfig, FilterType, FrameFormat, IdType,
+    RemoteTransmissionRequest,
+};
+use super::generic;
+
+#[doc = "Reader of field `ID`"]
+pub(crate) type ID_R = generic::R<u32, u32>;
+
+#[doc = "Reader of field `RTR`"]
+pub(crate) type RTR_R = generic::R<bool, RemoteTransmissionRequest>;
+impl RTR_R {
+    #[allow(dead_code)]
+    pub fn rtr(&self) -> RemoteTransmissionRequest {
+        match self.bits {
+            false => RemoteTransmissionRequest::TransmitDataFrame,
+            true => RemoteTransmissionRequest::TransmitRemoteFrame,
+        }
+    }
+    #[allow(dead_code)]
+    pub fn is_transmit_remote_frame(&self) -> bool {
+        self.rtr() == RemoteTransmissionRequest::TransmitRemoteFrame
+    }
+    #[allow(dead_code)]
+    pub fn is_transmit_data_frame(&self) -> bool {
+        self.rtr() == RemoteTransmissionRequest::TransmitDataFrame
+    }
+}
+
+#[doc = "Reader of field `XTD`"]
+pub(crate) type XTD_R = generic::R<bool, IdType>;
+impl XTD_R {
+    #[allow(dead_code)]
+    pub fn id_type(&self) -> IdType {
+        match self.bits() {
+            false => IdType::StandardId,
+            true => IdType::ExtendedId,
+        }
+    }
+    #[allow(dead_code)]
+    pub fn is_standard_id(&self) -> bool {
+        !self.is_extended_id()
+    }
+    #[allow(dead_code)]
+    pub fn is_extended_id(&self) -> bool {
+        self.id_type() == IdType::ExtendedId
+    }
+}
+
+#[doc = "Reader of field `ESI`"]
+pub(crate) type ESI_R = generic::R<bool, ErrorStateIndicator>;
+impl ESI_R {
+    #[allow(dead_code)]
+    pub fn error_state(&self) -> ErrorStateIndicator {
+        match self.bits() {
+            false => ErrorStateIndicator::ErrorActive,
+            true => ErrorStateIndicator::ErrorPassive,
+        }
+    }
+    #[allow(dead_code)]
+    pub fn is_error_active(&self) -> bool {
+        !self.is_error_passive()
+    }
+    #[allow(dead_code)]
+    pub fn is_error_passive(&self) -> bool {
+        self.error_state() == ErrorStateIndicator::ErrorPassive
+    }
+}
+
+#[doc = "Reader of field `DLC`"]
+pub(crate) type DLC_R = generic::R<u8, u8>;
+
+#[doc = "Reader of field `BRS`"]
+pub(crate) type BRS_R = generic::R<bool, BitRateSwitching>;
+impl BRS_R {
+    #[allow(dead_code)]
+    pub fn bit_rate_switching(&self) -> BitRateSwitching {
+        match self.bits() {
+            true => BitRateSwitching::WithBRS,
+            false => BitRateSwitching::WithoutBRS,
+        }
+    }
+    #[allow(dead_code)]
+    pub fn is_without_brs(&self) -> bool {
+        !self.is_with_brs()
+    }
+    #[allow(dead_code)]
+    pub fn is_with_brs(&self) -> bool {
+        self.bit_rate_switching() == BitRateSwitching::WithBRS
+    }
+}
+
+#[doc = "Reader of field `FDF`"]
+pub(crate) type FDF_R = generic::R<bool, FrameFormat>;
+impl FDF_R {
+    #[allow(dead_code)]
+    pub fn frame_format(&self) -> FrameFormat {
+        match self.bits() {
+            false => FrameFormat::Standard,
+            true => FrameFormat::Fdcan,
+        }
+    }
+    #[allow(dead_code)]
+    pub fn is_classic_format(&self) -> bool {
+        !self.is_fdcan_format()
+    }
+    #[allow(dead_code)]
+    pub fn is_fdcan_format(&self) -> bool {
+        self.frame_format() == FrameFormat::Fdcan
+    }
+}
+
+#[doc = "Reader of field `(S/E)FT`"]
+pub(crate) type FT_R = generic::R<u8, FilterType>;
+impl FT_R {
+    #[allow(dead_code)]
+    pub fn to_filter_type(&self) -> FilterType {
+        match self.bits() {
+            0b00 => FilterType::RangeFilter,
+            0b01 => FilterType::DualIdFilter,
+            0b10 => FilterType::ClassicFilter,
+            _ => FilterType::FilterDisabled,
+        }
+    }
+}
+
+#[doc = "Reader of field `(S/E)FEC`"]
+pub(crate) type FEC_R = generic::R<u8, FilterElementConfig>;
+impl FEC_R {
+    #[allow(dead_code)]
+    pub fn to_filter_element_config(&self) -> FilterElementConfig {
+        match self.bits() {
+            0b000 => FilterElementConfig::DisableFilterElement,
+            0b001 => FilterElementConfig::StoreInFifo0,
+            0b010 => FilterElementConfig::StoreInFifo1,
+            0b011 => FilterElementConfig::Reject,
+            0b100 => FilterElementConfig::SetPriority,
+            0b101 => FilterElementConfig::SetPriorityAndStoreInFifo0,
+            0b110 => FilterElementConfig::SetPriorityAndStoreInFifo1,
+            // 0b111 is not used on the STM32G4, the filter element has no effect
+            _ => FilterElementConfig::DisableFilterElement,
+        }
+    }
+}
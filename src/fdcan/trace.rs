@@ -0,0 +1,51 @@
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use super::id::Id;
+
+/// Signature of a trace hook, called with the id and the data length of a frame
+pub type TraceHook = fn(Id, u8);
+
+static ON_TRANSMIT: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+static ON_RECEIVE: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Sets the hook which is called for every frame put in a transmit mailbox
+///
+/// The hook is shared by all FdCan instances and replaces any previously set hook. It is called
+/// from within the transmit functions, so it runs in interrupt context when frames are
+/// transmitted from an interrupt. Keep it short, and do not access the FdCan peripheral from it.
+pub fn set_transmit_hook(hook: TraceHook) {
+    ON_TRANSMIT.store(hook as *mut (), Ordering::Relaxed);
+}
+
+/// Sets the hook which is called for every frame taken from a receive FIFO
+///
+/// The same constraints as for [`set_transmit_hook`] apply.
+pub fn set_receive_hook(hook: TraceHook) {
+    ON_RECEIVE.store(hook as *mut (), Ordering::Relaxed);
+}
+
+/// Removes both hooks
+pub fn clear_hooks() {
+    ON_TRANSMIT.store(core::ptr::null_mut(), Ordering::Relaxed);
+    ON_RECEIVE.store(core::ptr::null_mut(), Ordering::Relaxed);
+}
+
+#[inline]
+fn call(hook: &AtomicPtr<()>, id: Id, len: u8) {
+    let hook = hook.load(Ordering::Relaxed);
+    if !hook.is_null() {
+        // Safety: Only `TraceHook`s are stored in the hooks.
+        let hook = unsafe { core::mem::transmute::<*mut (), TraceHook>(hook) };
+        hook(id, len);
+    }
+}
+
+#[inline]
+pub(crate) fn transmit(id: Id, len: u8) {
+    call(&ON_TRANSMIT, id, len)
+}
+
+#[inline]
+pub(crate) fn receive(id: Id, len: u8) {
+    call(&ON_RECEIVE, id, len)
+}
@@ -68,6 +68,7 @@ pub mod can;
 pub mod delay;
 pub mod dma;
 pub mod exti;
+pub mod fdcan;
 pub mod flash;
 pub mod gpio;
 pub mod i2c;